@@ -0,0 +1,54 @@
+// Pre-event roster check without the GUI, for automated runs:
+//
+//     validate-roster roster.csv [more.csv ...]
+//
+// Prints every problem found and exits non-zero if any roster has one or
+// can't be read at all.
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() || paths.iter().any(|arg| arg == "-h" || arg == "--help") {
+        eprintln!("Usage: validate-roster <roster.csv> [more.csv ...]");
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for path in &paths {
+        let csv_data = match tauri_app_lib::load_roster(path) {
+            Ok(csv_data) => csv_data,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                failed = true;
+                continue;
+            }
+        };
+
+        // Unreadable rows would be dropped by the next save, so they count too
+        for row in &csv_data.skipped_rows {
+            println!("{}: line {}: {}", path, row.line, row.message);
+        }
+        let issues = tauri_app_lib::validate_players(&csv_data);
+        for issue in &issues {
+            println!("{}: {}: {}", path, issue.barcode, issue.message);
+        }
+
+        let summary = tauri_app_lib::summarize_roster(&csv_data.players);
+        let problems = csv_data.skipped_rows.len() + issues.len();
+        println!(
+            "{}: {} players on {} teams, {} problem(s)",
+            path,
+            summary.total_players,
+            summary.players_per_team.len(),
+            problems
+        );
+        failed |= problems > 0;
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
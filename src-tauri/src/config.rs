@@ -0,0 +1,147 @@
+use anyhow::{Context, Result as AnyhowResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock, RwLock};
+
+// Everything the app remembers between launches, saved as settings.json in the
+// app config dir. New fields need a default so older files keep loading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Where backups go instead of next to the original; relative paths are
+    // resolved against the roster's own folder (e.g. ".backups")
+    pub backup_dir: Option<String>,
+    // How many backups to keep per roster; 0 keeps them all
+    pub max_backups: usize,
+    // Barcodes repo cloned by git_pull, and the sibling folder it lives in
+    pub git_repo_url: String,
+    pub git_repo_folder: String,
+    // Sibling folder holding this app's own repo, where run_update finds its script
+    pub app_repo_folder: String,
+    // Commit author for git_push; unset falls back to the machine's git config
+    pub git_user_name: Option<String>,
+    pub git_user_email: Option<String>,
+    // Minimum level written to the log file: error, warn, info, debug or trace
+    pub log_level: String,
+    // View-only stations: every command that changes a roster, a backup or the
+    // barcodes repo refuses to run
+    pub read_only: bool,
+    // Find players ignoring case and surrounding spaces, for scanners that send
+    // "ab123" for a stored "AB123"; off keeps barcodes exact
+    pub case_insensitive_barcodes: bool,
+    // Extra column that identifies players whose barcode is still blank, for order
+    // files exported before barcodes are assigned (e.g. "Order ID")
+    pub fallback_match_column: Option<String>,
+    // Rosters opened most recently, newest first, for File > Recent
+    pub recent_files: Vec<String>,
+    // Main window placement when the app was last closed
+    pub window: Option<WindowGeometry>,
+}
+
+// Physical pixels, as the window reports them; size and position are from the
+// last time the window wasn't maximized
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub maximized: bool,
+}
+
+pub(crate) const DEFAULT_GIT_REPO_URL: &str = "git@github.com:SonicKurt/mvs-job-barcodes.git";
+pub(crate) const DEFAULT_GIT_REPO_FOLDER: &str = "mvs-job-barcodes";
+pub(crate) const DEFAULT_APP_REPO_FOLDER: &str = "MVS-form-filler";
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            backup_dir: None,
+            max_backups: 20,
+            git_repo_url: DEFAULT_GIT_REPO_URL.to_string(),
+            git_repo_folder: DEFAULT_GIT_REPO_FOLDER.to_string(),
+            app_repo_folder: DEFAULT_APP_REPO_FOLDER.to_string(),
+            git_user_name: None,
+            git_user_email: None,
+            log_level: "info".to_string(),
+            read_only: false,
+            case_insensitive_barcodes: false,
+            fallback_match_column: None,
+            recent_files: Vec::new(),
+            window: None,
+        }
+    }
+}
+
+static CONFIG: LazyLock<RwLock<Config>> = LazyLock::new(|| RwLock::new(Config::default()));
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+// Called once during setup; a missing or unreadable file just means defaults
+pub(crate) fn init_config(config_dir: &Path) {
+    let path = config_dir.join("settings.json");
+    if let Some(loaded) = load_config(&path) {
+        *CONFIG.write().unwrap() = loaded;
+    }
+    let _ = CONFIG_PATH.set(path);
+}
+
+fn load_config(path: &Path) -> Option<Config> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_config(path: &Path, config: &Config) -> AnyhowResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(config)?)
+        .with_context(|| format!("Failed to save settings to {}", path.display()))
+}
+
+pub(crate) fn config() -> Config {
+    CONFIG.read().unwrap().clone()
+}
+
+pub(crate) fn update_config(change: impl FnOnce(&mut Config)) -> AnyhowResult<()> {
+    let mut current = CONFIG.write().unwrap();
+    change(&mut current);
+
+    if let Some(path) = CONFIG_PATH.get() {
+        save_config(path, &current)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn older_settings_files_fill_in_new_fields() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("old_settings.json");
+        fs::write(&path, r#"{ "max_backups": 5, "git_repo_folder": "barcodes" }"#).unwrap();
+
+        let loaded = load_config(&path).unwrap();
+        assert_eq!(loaded.max_backups, 5);
+        assert_eq!(loaded.git_repo_folder, "barcodes");
+        assert_eq!(loaded.git_repo_url, DEFAULT_GIT_REPO_URL);
+        assert_eq!(loaded.window, None);
+
+        let saved = Config {
+            window: Some(WindowGeometry {
+                width: 1200,
+                height: 800,
+                x: 40,
+                y: 30,
+                maximized: false,
+            }),
+            ..loaded
+        };
+        save_config(&path, &saved).unwrap();
+        assert_eq!(load_config(&path).unwrap().window, saved.window);
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+use std::fmt;
+
+// Error returned by every command. It serializes as `{ kind, message, ... }` so the
+// frontend can tell a missing file from a git failure from bad input and react
+// accordingly (retry, inline field error, ...), instead of only getting a string.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind")]
+pub enum AppError {
+    FileNotFound {
+        message: String,
+    },
+    Parse {
+        message: String,
+    },
+    Validation {
+        message: String,
+    },
+    NotFound {
+        message: String,
+    },
+    Git {
+        message: String,
+        // Plain-language next step for the operator, when we recognize the failure
+        suggestion: Option<String>,
+        // Git's own output, kept for debugging
+        raw: Option<String>,
+    },
+    Io {
+        message: String,
+    },
+    // A write was attempted while the read-only setting is on
+    ReadOnly {
+        message: String,
+    },
+    // The request looks like a mistake; sending it again with confirmation goes ahead
+    NeedsConfirmation {
+        message: String,
+        suggestion: Option<String>,
+    },
+}
+
+impl AppError {
+    pub fn file_not_found(message: impl Into<String>) -> Self {
+        AppError::FileNotFound { message: message.into() }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        AppError::Parse { message: message.into() }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation { message: message.into() }
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound { message: message.into() }
+    }
+
+    pub fn git(message: impl Into<String>) -> Self {
+        AppError::Git {
+            message: message.into(),
+            suggestion: None,
+            raw: None,
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        AppError::Io { message: message.into() }
+    }
+
+    pub fn read_only(message: impl Into<String>) -> Self {
+        AppError::ReadOnly { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::FileNotFound { message }
+            | AppError::Parse { message }
+            | AppError::Validation { message }
+            | AppError::NotFound { message }
+            | AppError::Git { message, .. }
+            | AppError::Io { message }
+            | AppError::ReadOnly { message }
+            | AppError::NeedsConfirmation { message, .. } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// The file helpers use anyhow internally; recover the most specific kind from
+// the error chain and keep the full context chain as the message
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<AppError>() {
+            Ok(app_error) => return app_error,
+            Err(err) => err,
+        };
+
+        let message = format!("{:#}", err);
+        if err.downcast_ref::<crate::MissingHeadersError>().is_some()
+            || err.downcast_ref::<csv::Error>().is_some()
+        {
+            return AppError::parse(message);
+        }
+        match err.downcast_ref::<std::io::Error>() {
+            Some(io) if io.kind() == std::io::ErrorKind::NotFound => AppError::file_not_found(message),
+            _ => AppError::io(message),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            AppError::file_not_found(err.to_string())
+        } else {
+            AppError::io(err.to_string())
+        }
+    }
+}
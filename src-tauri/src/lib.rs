@@ -1,14 +1,26 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Arc, LazyLock, Mutex};
 use chrono::Utc;
 use anyhow::{Context, Result as AnyhowResult};
+use calamine::{DataType, Reader};
 use tauri::menu::{MenuBuilder, SubmenuBuilder, MenuItemBuilder};
 use tauri::{Manager, Emitter};
+use tauri_plugin_opener::OpenerExt;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+mod config;
+mod error;
+
+pub use config::{Config, WindowGeometry};
+use config::{
+    config, init_config, update_config, DEFAULT_APP_REPO_FOLDER, DEFAULT_GIT_REPO_FOLDER, DEFAULT_GIT_REPO_URL,
+};
+pub use error::AppError;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Player {
     #[serde(rename = "Barcode Number")]
     pub barcode: String,
@@ -30,443 +42,6143 @@ pub struct Player {
     pub products: String,
     #[serde(rename = "Packages")]
     pub packages: String,
-    #[serde(flatten)]
+    // Products and Packages split into item codes for display; the raw strings
+    // above stay the source of truth when saving
+    #[serde(default)]
+    pub product_items: Vec<String>,
+    #[serde(default)]
+    pub package_items: Vec<String>,
+    #[serde(flatten, deserialize_with = "deserialize_other_fields")]
     pub other_fields: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// Extra columns are text in the CSV, but JSON from other tools often types them
+// (`"Photo Count": 2`, `"Paid": true`); keep those as the text the CSV would hold
+// rather than rejecting the whole player
+fn deserialize_other_fields<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let fields = HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    fields
+        .into_iter()
+        .map(|(column, value)| {
+            let text = match value {
+                serde_json::Value::String(text) => text,
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+                serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+                    return Err(serde::de::Error::custom(format!(
+                        "\"{}\" must be text, a number or true/false",
+                        column
+                    )));
+                }
+            };
+            Ok((column, text))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CSVData {
     pub players: Vec<Player>,
     pub teams: Vec<String>,
     pub file_path: String,
+    // Header row as it appeared in the file, so rewrites keep the column order
+    pub headers: Vec<String>,
+    pub delimiter: char,
+    pub quote_style: QuoteStyle,
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    // Set when the folder's .csv-dialect.json decided how the file was read, so
+    // saves write it back the same way
+    #[serde(default)]
+    pub dialect: Option<CsvDialect>,
+    pub skipped_rows: Vec<RowError>,
+    pub duplicates: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PlayerUpdate {
-    pub barcode: String,
-    pub first_name: String,
-    pub last_name: String,
-    pub cell_phone: String,
-    pub email: String,
-    pub coach: String,
-    pub products: String,
-    pub packages: String,
+// How the file quoted its fields, so rewrites don't churn every line in git
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum QuoteStyle {
+    // Only fields that need it (the csv crate's default)
+    #[default]
+    Necessary,
+    // Every field, as some exporters do
+    Always,
 }
 
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+// Dominant record terminator in the file; rosters move between macOS and Windows
+// and flipping it turns every save into a whole-file diff
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
 }
 
-#[tauri::command]
-async fn load_csv(file_path: String) -> Result<CSVData, String> {
-    load_csv_file(&file_path).await.map_err(|e| e.to_string())
+impl LineEnding {
+    fn terminator(self) -> csv::Terminator {
+        match self {
+            LineEnding::Lf => csv::Terminator::Any(b'\n'),
+            LineEnding::Crlf => csv::Terminator::CRLF,
+        }
+    }
 }
 
-#[tauri::command]
-async fn save_player(file_path: String, player_update: PlayerUpdate) -> Result<(), String> {
-    save_player_data(&file_path, player_update).await.map_err(|e| e.to_string())
+// Contents of an optional .csv-dialect.json next to the roster, for leagues whose
+// exports the sniffing gets wrong; anything left out is still detected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CsvDialect {
+    pub delimiter: Option<char>,
+    pub quote: Option<char>,
+    pub has_headers: bool,
+    // Column names for files without a header row; empty means the standard layout
+    pub columns: Vec<String>,
 }
 
-#[tauri::command]
-async fn create_backup(file_path: String) -> Result<String, String> {
-    create_backup_file(&file_path).await.map_err(|e| e.to_string())
+impl Default for CsvDialect {
+    fn default() -> Self {
+        CsvDialect {
+            delimiter: None,
+            quote: None,
+            has_headers: true,
+            columns: Vec::new(),
+        }
+    }
 }
 
-#[tauri::command]
-async fn write_csv_file(file_path: String, csv_content: String) -> Result<(), String> {
-    write_csv_content(&file_path, csv_content).await.map_err(|e| e.to_string())
+impl CsvDialect {
+    fn quote_byte(&self) -> u8 {
+        self.quote.map_or(b'"', |q| q as u8)
+    }
 }
 
-#[tauri::command]
-fn run_update() -> Result<(), String> {
-    // Find the repo directory
-    // For AppImage: APPIMAGE env var points to the .AppImage file, repo is sibling dir
-    // For macOS: The .app is in parent dir, repo is sibling dir
-    let repo_dir = if cfg!(target_os = "linux") {
-        // On Linux with AppImage, use APPIMAGE env var
-        std::env::var("APPIMAGE")
-            .ok()
-            .and_then(|appimage_path| {
-                Path::new(&appimage_path)
-                    .parent()
-                    .map(|p| p.join("MVS-form-filler"))
-            })
-            .ok_or("Could not determine repo directory from APPIMAGE")?
-    } else {
-        // On macOS, the .app bundle is in parent dir of repo
-        std::env::current_exe()
-            .map_err(|e| e.to_string())?
-            .parent() // Contents/MacOS
-            .and_then(|p| p.parent()) // Contents
-            .and_then(|p| p.parent()) // .app bundle
-            .and_then(|p| p.parent()) // parent dir
-            .map(|p| p.join("MVS-form-filler"))
-            .ok_or("Could not determine repo directory")?
-    };
+const DIALECT_FILE_NAME: &str = ".csv-dialect.json";
 
-    let update_script = repo_dir.join("update.sh");
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
 
-    if !update_script.exists() {
-        return Err(format!("Update script not found at: {}", update_script.display()));
-    }
+// End-of-day tallies; maps are keyed by team or item code and sorted for display
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RosterSummary {
+    pub total_players: usize,
+    pub players_per_team: std::collections::BTreeMap<String, usize>,
+    pub product_counts: std::collections::BTreeMap<String, usize>,
+    pub package_counts: std::collections::BTreeMap<String, usize>,
+}
 
-    // Open a terminal and run the update script
-    #[cfg(target_os = "linux")]
-    {
-        // Try common Linux terminal emulators
-        let terminals = [
-            ("gnome-terminal", vec!["--", "bash", "-c"]),
-            ("konsole", vec!["-e", "bash", "-c"]),
-            ("xfce4-terminal", vec!["-e"]),
-            ("xterm", vec!["-e"]),
-        ];
+// A row of the check-in sheet
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TeamCount {
+    pub team: String,
+    pub players: usize,
+}
 
-        for (term, args) in terminals.iter() {
-            let mut cmd = Command::new(term);
-            for arg in args {
-                cmd.arg(arg);
-            }
-            cmd.arg(update_script.to_str().unwrap());
+// Players entered with the same email or phone. Siblings share these legitimately,
+// so groups are for review, not errors
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContactGroup {
+    pub kind: ContactKind,
+    // As entered on the first player of the group
+    pub contact: String,
+    pub players: Vec<Player>,
+}
 
-            if cmd.spawn().is_ok() {
-                return Ok(());
-            }
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ContactKind {
+    Email,
+    Phone,
+}
 
-        return Err("Could not find a terminal emulator".to_string());
-    }
+// One problem found while pre-flighting a roster
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub barcode: String,
+    pub message: String,
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        Command::new("open")
-            .arg("-a")
-            .arg("Terminal")
-            .arg(&update_script)
-            .spawn()
-            .map_err(|e| e.to_string())?;
-        return Ok(());
-    }
+// A league's own rules, from a schema file the deployment points at:
+//
+//     { "columns": { "Email": { "required": true, "type": "email" },
+//                    "Coach": { "allowed": ["Y", "N"] } } }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RosterSchema {
+    pub columns: std::collections::BTreeMap<String, ColumnRule>,
+}
 
-    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
-    {
-        return Err("Unsupported operating system".to_string());
-    }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnRule {
+    // The file must have the column and every player a value in it
+    pub required: bool,
+    #[serde(rename = "type")]
+    pub field_type: Option<FieldType>,
+    // Accepted values, compared ignoring case; empty allows anything
+    pub allowed: Vec<String>,
 }
 
-async fn load_csv_file(file_path: &str) -> AnyhowResult<CSVData> {
-    let content = fs::read_to_string(file_path)
-        .with_context(|| format!("Failed to read file: {}", file_path))?;
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Email,
+    Phone,
+    Number,
+}
 
-    let mut reader = csv::Reader::from_reader(content.as_bytes());
-    
-    let mut players = Vec::new();
-    let mut teams = std::collections::HashSet::new();
+// Columns without which a file can't be an order CSV
+const REQUIRED_HEADERS: [&str; 4] = ["Barcode Number", "Team", "First Name", "Last Name"];
 
-    for result in reader.deserialize() {
-        let player: Player = result?;
-        teams.insert(player.team.clone());
-        players.push(player);
+#[derive(Debug)]
+pub struct MissingHeadersError {
+    pub missing: Vec<String>,
+}
+
+impl std::fmt::Display for MissingHeadersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "This doesn't look like an order CSV — missing columns: {}",
+            self.missing.join(", ")
+        )
     }
+}
 
-    let mut teams_vec: Vec<String> = teams.into_iter().collect();
-    teams_vec.sort();
+impl std::error::Error for MissingHeadersError {}
 
-    Ok(CSVData {
-        players,
-        teams: teams_vec,
-        file_path: file_path.to_string(),
-    })
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub path: String,
+    pub timestamp: chrono::NaiveDateTime,
 }
 
-async fn save_player_data(file_path: &str, player_update: PlayerUpdate) -> AnyhowResult<()> {
-    // First, create a backup
-    create_backup_file(file_path).await?;
+// Disk taken by one roster's backups, newest first like list_backups
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupUsage {
+    pub total_bytes: u64,
+    pub backups: Vec<BackupSize>,
+}
 
-    // Load current data
-    let mut csv_data = load_csv_file(file_path).await?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupSize {
+    pub path: String,
+    pub timestamp: chrono::NaiveDateTime,
+    pub bytes: u64,
+}
 
-    // Find and update the player
-    if let Some(player) = csv_data.players.iter_mut().find(|p| p.barcode == player_update.barcode) {
-        player.first_name = player_update.first_name;
-        player.last_name = player_update.last_name;
-        player.cell_phone = player_update.cell_phone;
-        player.email = player_update.email;
-        player.coach = player_update.coach;
-        player.products = player_update.products;
-        player.packages = player_update.packages;
-    }
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
 
-    // Write back to file
-    let mut writer = csv::Writer::from_path(file_path)?;
-    
-    for player in &csv_data.players {
-        writer.serialize(player)?;
-    }
-    
-    writer.flush()?;
-    Ok(())
+// How one barcode differs between two snapshots of a roster
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerDiff {
+    pub barcode: String,
+    pub kind: DiffKind,
+    // Only filled in for Changed
+    pub fields: Vec<FieldChange>,
 }
 
-async fn write_csv_content(file_path: &str, csv_content: String) -> AnyhowResult<()> {
-    use std::path::Path;
-    
-    let path = Path::new(file_path);
-    
-    // If it's just a filename (no directory separators), save to Downloads folder
-    let target_path = if path.parent().is_none() || path.parent() == Some(Path::new("")) {
-        // Get the user's home directory and create Downloads path
-        let home_dir = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let downloads_path = Path::new(&home_dir).join("Downloads").join(file_path);
-        downloads_path
-    } else {
-        path.to_path_buf()
-    };
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+}
 
-    // Create backup before overwriting if file exists
-    if target_path.exists() {
-        create_backup_file(target_path.to_str().unwrap()).await?;
-    }
-    
-    // Write new content to the file
-    fs::write(&target_path, csv_content)
-        .with_context(|| format!("Failed to write CSV content to file: {}", target_path.display()))?;
-    
-    println!("CSV file saved to: {}", target_path.display());
-    Ok(())
+// One field change made through save_player, appended to the roster's edit log
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct EditLogEntry {
+    pub timestamp: chrono::DateTime<Utc>,
+    pub barcode: String,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
 }
 
-async fn create_backup_file(file_path: &str) -> AnyhowResult<String> {
-    let path = Path::new(file_path);
-    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-    
-    let backup_path = if let Some(parent) = path.parent() {
-        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("backup");
-        let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("csv");
-        parent.join(format!("{}_backup_{}.{}", stem, timestamp, extension))
-    } else {
-        Path::new(&format!("backup_{}.csv", timestamp)).to_path_buf()
-    };
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveResult {
+    // The player as written, including normalized values
+    pub player: Player,
+    // Roster teams after the save, so a team change shows up without a reload
+    pub teams: Vec<String>,
+    // False when the file already matched and nothing was written
+    pub changed: bool,
+}
 
-    fs::copy(file_path, &backup_path)
-        .with_context(|| format!("Failed to create backup at {:?}", backup_path))?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WriteResult {
+    pub path: String,
+    pub changed: bool,
+}
 
-    Ok(backup_path.to_string_lossy().to_string())
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MergeResult {
+    pub added: usize,
+    // Incoming rows identical to a player already in the base roster, or without a barcode
+    pub skipped: usize,
+    // Barcodes in both files with different details; left as they are in the base for the operator to decide
+    pub conflicts: Vec<String>,
+    pub changed: bool,
 }
 
-// Helper function to get the parent directory where sister folders are located
-fn get_parent_dir() -> Result<std::path::PathBuf, String> {
-    if cfg!(target_os = "linux") {
-        // On Linux with AppImage, use APPIMAGE env var
-        std::env::var("APPIMAGE")
-            .ok()
-            .and_then(|appimage_path| {
-                Path::new(&appimage_path)
-                    .parent()
-                    .map(|p| p.to_path_buf())
-            })
-            .ok_or_else(|| "Could not determine parent directory from APPIMAGE".to_string())
-    } else {
-        // On macOS, the .app bundle is in parent dir
-        std::env::current_exe()
-            .map_err(|e| e.to_string())?
-            .parent() // Contents/MacOS
-            .and_then(|p| p.parent()) // Contents
-            .and_then(|p| p.parent()) // .app bundle
-            .and_then(|p| p.parent()) // parent dir
-            .map(|p| p.to_path_buf())
-            .ok_or_else(|| "Could not determine parent directory".to_string())
-    }
+// Spellings of a team that normalize_teams folded into one name
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TeamMerge {
+    pub canonical: String,
+    // As they appeared in the file, sorted; may include the canonical spelling itself
+    pub variants: Vec<String>,
+    pub players: usize,
 }
 
-#[tauri::command]
-fn git_pull() -> Result<String, String> {
-    let parent_dir = get_parent_dir()?;
-    let barcodes_dir = parent_dir.join("mvs-job-barcodes");
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemapResult {
+    pub remapped: usize,
+    // Old barcodes in the mapping that no player has
+    pub unmatched_mapping: Vec<String>,
+    // Players the mapping doesn't mention, who keep their barcode
+    pub unmapped_players: Vec<String>,
+    pub changed: bool,
+}
 
-    if !barcodes_dir.exists() {
-        // Clone the repository
-        println!("Cloning mvs-job-barcodes repository...");
-        let output = Command::new("git")
-            .args(["clone", "git@github.com:SonicKurt/mvs-job-barcodes.git"])
-            .current_dir(&parent_dir)
-            .output()
-            .map_err(|e| format!("Failed to run git clone: {}", e))?;
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub added: usize,
+    pub updated: usize,
+    // Matched by barcode but already identical
+    pub unchanged: usize,
+    pub changed: bool,
+}
 
-        if output.status.success() {
-            Ok("Repository cloned successfully!".to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Git clone failed: {}", stderr))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerUpdate {
+    pub barcode: String,
+    pub team: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub jersey_number: String,
+    pub cell_phone: String,
+    pub email: String,
+    pub coach: String,
+    pub products: String,
+    pub packages: String,
+    // Values for league-specific columns kept in Player.other_fields ("Photo Status", ...)
+    #[serde(default)]
+    pub extra_fields: HashMap<String, String>,
+    // Set when the operator really means a new team that looks like a typo of an existing one
+    #[serde(default)]
+    pub confirm_new_team: bool,
+}
+
+impl PlayerUpdate {
+    // Leading/trailing whitespace only; "Van Der Berg" keeps its inner spaces
+    fn trim_fields(&mut self) {
+        for field in [
+            &mut self.barcode,
+            &mut self.team,
+            &mut self.first_name,
+            &mut self.last_name,
+            &mut self.jersey_number,
+            &mut self.cell_phone,
+            &mut self.email,
+            &mut self.coach,
+            &mut self.products,
+            &mut self.packages,
+        ] {
+            *field = field.trim().to_string();
         }
-    } else {
-        // Pull latest changes
-        println!("Pulling latest changes in mvs-job-barcodes...");
-        let output = Command::new("git")
-            .args(["pull"])
-            .current_dir(&barcodes_dir)
-            .output()
-            .map_err(|e| format!("Failed to run git pull: {}", e))?;
+    }
 
-        if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            Ok(format!("Pull successful: {}", stdout.trim()))
-        } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("Git pull failed: {}", stderr))
+    // The barcode, or while that's blank the fallback_match_column value sent in
+    // extra_fields. Neither being set is an error; an empty key would match
+    // whichever blank-barcode row comes first
+    fn match_key(&self, settings: &Config) -> Result<&str, AppError> {
+        if !self.barcode.is_empty() {
+            return Ok(&self.barcode);
+        }
+        match &settings.fallback_match_column {
+            Some(column) => match self.extra_fields.get(column).map(|value| value.trim()) {
+                Some(value) if !value.is_empty() => Ok(value),
+                _ => Err(AppError::validation(format!("The player has no barcode or {}", column))),
+            },
+            None => Err(AppError::validation("The player has no barcode")),
         }
     }
 }
 
+const MAX_RECENT_FILES: usize = 10;
+const RECENT_MENU_PREFIX: &str = "recent:";
+// Log file stem inside the app log dir
+const LOG_FILE_NAME: &str = "mvs-photo-form";
+// Rolled-over log files kept beside the current one
+const LOG_FILES_KEPT: usize = 5;
+
+// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
-fn git_push(commit_message: String) -> Result<String, String> {
-    let parent_dir = get_parent_dir()?;
-    let barcodes_dir = parent_dir.join("mvs-job-barcodes");
+fn greet(name: &str) -> String {
+    format!("Hello, {}! You've been greeted from Rust!", name)
+}
 
-    if !barcodes_dir.exists() {
-        return Err("mvs-job-barcodes folder not found. Please pull first.".to_string());
-    }
+#[derive(Debug, Clone, Serialize)]
+struct LoadProgress {
+    rows: usize,
+    total: usize,
+}
 
-    // Git add
-    println!("Adding changes...");
-    let add_output = Command::new("git")
-        .args(["add", "."])
-        .current_dir(&barcodes_dir)
-        .output()
-        .map_err(|e| format!("Failed to run git add: {}", e))?;
+#[derive(Debug, Clone, Serialize)]
+struct LoadComplete {
+    players: usize,
+}
 
-    if !add_output.status.success() {
-        let stderr = String::from_utf8_lossy(&add_output.stderr);
-        return Err(format!("Git add failed: {}", stderr));
-    }
+// Sent after a player is saved, added or deleted so other views can re-query
+#[derive(Debug, Clone, Serialize)]
+struct RosterChanged {
+    file_path: String,
+    barcode: String,
+}
 
-    // Git commit
-    println!("Committing changes...");
-    let commit_output = Command::new("git")
-        .args(["commit", "-m", &commit_message])
-        .current_dir(&barcodes_dir)
-        .output()
-        .map_err(|e| format!("Failed to run git commit: {}", e))?;
+fn emit_roster_changed(app: &tauri::AppHandle, file_path: &str, barcode: &str) {
+    let _ = app.emit(
+        "roster-changed",
+        RosterChanged {
+            file_path: file_path.to_string(),
+            barcode: barcode.to_string(),
+        },
+    );
+}
 
-    if !commit_output.status.success() {
-        let stderr = String::from_utf8_lossy(&commit_output.stderr);
-        let stdout = String::from_utf8_lossy(&commit_output.stdout);
-        // Check if it's just "nothing to commit"
-        if stdout.contains("nothing to commit") || stderr.contains("nothing to commit") {
-            return Err("Nothing to commit - no changes detected.".to_string());
+// Work done since the app started, for a "12 players updated this session" line.
+// In memory only, so a relaunch starts over
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub started_at: chrono::DateTime<Utc>,
+    pub edited: usize,
+    // Duplicated players count as added
+    pub added: usize,
+    pub deleted: usize,
+}
+
+impl SessionStats {
+    fn new() -> Self {
+        SessionStats {
+            started_at: Utc::now(),
+            edited: 0,
+            added: 0,
+            deleted: 0,
         }
-        return Err(format!("Git commit failed: {}", stderr));
     }
+}
 
-    // Git push
-    println!("Pushing changes...");
-    let push_output = Command::new("git")
-        .args(["push"])
-        .current_dir(&barcodes_dir)
-        .output()
-        .map_err(|e| format!("Failed to run git push: {}", e))?;
+// Takes the managed Mutex rather than an AppHandle so tests can count into their own
+fn record_session(stats: &Mutex<SessionStats>, change: impl FnOnce(&mut SessionStats)) {
+    change(&mut stats.lock().unwrap());
+}
 
-    if push_output.status.success() {
-        Ok("Changes pushed successfully!".to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&push_output.stderr);
-        Err(format!("Git push failed: {}", stderr))
+#[tauri::command]
+fn session_stats(state: tauri::State<'_, Mutex<SessionStats>>) -> SessionStats {
+    *state.lock().unwrap()
+}
+
+#[tauri::command]
+async fn load_csv(app: tauri::AppHandle, file_path: String) -> Result<CSVData, AppError> {
+    // Large combined-league rosters take a moment; let the frontend show a progress bar
+    let mut report = |rows, total| {
+        let _ = app.emit("csv-load-progress", LoadProgress { rows, total });
+    };
+    let csv_data = load_csv_file_reporting(&file_path, &mut report).map_err(AppError::from)?;
+
+    let _ = app.emit("csv-load-complete", LoadComplete { players: csv_data.players.len() });
+    // The roster loaded fine; a settings write failure shouldn't report otherwise
+    if let Err(e) = add_recent_file(app, file_path) {
+        log::warn!("Failed to remember recent file: {}", e);
     }
+    Ok(csv_data)
 }
 
+// Also called by the frontend after it opens a roster itself
 #[tauri::command]
-fn get_barcodes_path() -> Result<String, String> {
-    let parent_dir = get_parent_dir()?;
-    let barcodes_dir = parent_dir.join("mvs-job-barcodes");
-    Ok(barcodes_dir.to_string_lossy().to_string())
+fn add_recent_file(app: tauri::AppHandle, file_path: String) -> Result<(), AppError> {
+    update_config(|settings| push_recent_file(&mut settings.recent_files, &file_path)).map_err(AppError::from)?;
+    if let Err(e) = install_menu(&app) {
+        log::warn!("Failed to refresh the Recent menu: {}", e);
+    }
+    Ok(())
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_fs::init())
+// Recent entries whose files still exist
+fn recent_files() -> Vec<String> {
+    config()
+        .recent_files
+        .into_iter()
+        .filter(|path| Path::new(path).exists())
+        .collect()
+}
+
+fn push_recent_file(recent: &mut Vec<String>, file_path: &str) {
+    recent.retain(|path| path != file_path && Path::new(path).exists());
+    recent.insert(0, file_path.to_string());
+    recent.truncate(MAX_RECENT_FILES);
+}
+
+#[tauri::command]
+async fn save_player(
+    app: tauri::AppHandle,
+    file_path: String,
+    player_update: PlayerUpdate,
+) -> Result<SaveResult, AppError> {
+    ensure_writable()?;
+    let result = save_player_data(&file_path, player_update).await.map_err(AppError::from)?;
+    if result.changed {
+        emit_roster_changed(&app, &file_path, &result.player.barcode);
+        record_session(&app.state::<Mutex<SessionStats>>(), |stats| stats.edited += 1);
+    }
+    // The edit is in the roster now; a stale draft would offer to undo it on relaunch
+    let cleared = match drafts_path(&app) {
+        Ok(path) => remove_draft(&path, &result.player.barcode).await,
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = cleared {
+        log::warn!("Failed to clear draft for {}: {}", result.player.barcode, e);
+    }
+    Ok(result)
+}
+
+// Autosave of a half-filled player form, so a crash doesn't lose it. Drafts are
+// kept per barcode until save_player succeeds or the form is discarded
+#[tauri::command]
+async fn save_draft(app: tauri::AppHandle, draft: PlayerUpdate) -> Result<(), AppError> {
+    store_draft(&drafts_path(&app)?, draft).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn get_draft(app: tauri::AppHandle, barcode: String) -> Result<Option<PlayerUpdate>, AppError> {
+    let mut drafts = read_drafts(&drafts_path(&app)?).map_err(AppError::from)?;
+    Ok(drafts.remove(barcode.trim()))
+}
+
+#[tauri::command]
+async fn clear_draft(app: tauri::AppHandle, barcode: String) -> Result<(), AppError> {
+    remove_draft(&drafts_path(&app)?, &barcode).await.map_err(AppError::from)
+}
+
+// For a "confirm changes" dialog, and for working out why git shows a diff
+#[tauri::command]
+async fn preview_save(file_path: String, player_update: PlayerUpdate) -> Result<String, AppError> {
+    preview_save_data(&file_path, player_update).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn parse_products(products: String) -> Vec<String> {
+    split_order_items(&products)
+}
+
+#[tauri::command]
+async fn get_edit_log(file_path: String) -> Result<Vec<EditLogEntry>, AppError> {
+    read_edit_log(Path::new(&file_path)).map_err(AppError::from)
+}
+
+// Header row in file order, extras included, so the UI can build its form per league
+#[tauri::command]
+async fn get_columns(file_path: String) -> Result<Vec<String>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(csv_data.headers)
+}
+
+#[tauri::command]
+async fn get_player(file_path: String, barcode: String) -> Result<Player, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    let settings = config();
+    // Barcodes are compared as text: "00123" and "123" are different players
+    csv_data
+        .players
+        .into_iter()
+        .find(matches_player_key(&barcode, &settings))
+        .ok_or_else(|| AppError::not_found(format!("No player with barcode {} found", barcode)))
+}
+
+// Read the barcode off a photo of a printed label (Code 128 on ours, but any
+// format rxing knows is accepted); feed the result into get_player
+#[tauri::command]
+async fn decode_barcode(image_path: String) -> Result<String, AppError> {
+    if !Path::new(&image_path).is_file() {
+        return Err(AppError::file_not_found(format!("Image not found: {}", image_path)));
+    }
+
+    let result = rxing::helpers::detect_in_file(&image_path, None).map_err(|e| {
+        log::debug!("Barcode decode failed for {}: {}", image_path, e);
+        AppError::not_found("No barcode found in the image. Try a closer, sharper photo of the label.")
+    })?;
+
+    Ok(result.getText().trim().to_string())
+}
+
+#[tauri::command]
+async fn search_players(file_path: String, query: String) -> Result<Vec<Player>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(rank_players(csv_data.players, &query))
+}
+
+#[tauri::command]
+async fn players_for_team(file_path: String, team: String) -> Result<Vec<Player>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+
+    // An unknown team is just an empty list, not an error
+    let mut players: Vec<Player> = csv_data.players.into_iter().filter(|p| p.team == team).collect();
+    players.sort_by(|a, b| compare_jersey_numbers(&a.jersey_number, &b.jersey_number));
+    Ok(players)
+}
+
+// Check-in order: the front desk works through the roster by last name
+#[tauri::command]
+async fn players_alphabetical(file_path: String) -> Result<Vec<Player>, AppError> {
+    let mut players = load_csv_file(&file_path).await.map_err(AppError::from)?.players;
+    players.sort_by(compare_player_names);
+    Ok(players)
+}
+
+#[tauri::command]
+async fn validate_roster(file_path: String) -> Result<Vec<ValidationIssue>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(validate_players(&csv_data))
+}
+
+// Per-deployment checks declared in a schema file, instead of validate_roster's built-in ones
+#[tauri::command]
+async fn validate_against_schema(file_path: String, schema_path: String) -> Result<Vec<ValidationIssue>, AppError> {
+    let schema = load_schema(&schema_path).map_err(AppError::from)?;
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(validate_with_schema(&csv_data, &schema))
+}
+
+// Inline feedback while typing; `field` is the CSV column name ("Email", "Cell Phone", ...)
+#[tauri::command]
+fn validate_field(field: String, value: String) -> Result<(), AppError> {
+    check_field(&field, &value).map_err(AppError::validation)
+}
+
+#[tauri::command]
+async fn roster_summary(file_path: String) -> Result<RosterSummary, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(summarize_roster(&csv_data.players))
+}
+
+// Who hasn't ordered yet, in roster order
+#[tauri::command]
+async fn incomplete_orders(file_path: String) -> Result<Vec<Player>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(csv_data.players.into_iter().filter(has_no_order).collect())
+}
+
+#[tauri::command]
+async fn find_shared_contacts(file_path: String) -> Result<Vec<ContactGroup>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(shared_contacts(&csv_data.players))
+}
+
+#[tauri::command]
+async fn teams_with_counts(file_path: String) -> Result<Vec<TeamCount>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(count_teams(&csv_data.players))
+}
+
+#[tauri::command]
+async fn undo_last_edit(file_path: String) -> Result<Player, AppError> {
+    ensure_writable()?;
+    undo_last_edit_data(&file_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn delete_player(app: tauri::AppHandle, file_path: String, barcode: String) -> Result<(), AppError> {
+    ensure_writable()?;
+    delete_player_data(&file_path, &barcode).await.map_err(AppError::from)?;
+    emit_roster_changed(&app, &file_path, &barcode);
+    record_session(&app.state::<Mutex<SessionStats>>(), |stats| stats.deleted += 1);
+    Ok(())
+}
+
+#[tauri::command]
+async fn add_player(app: tauri::AppHandle, file_path: String, player: Player) -> Result<(), AppError> {
+    ensure_writable()?;
+    let barcode = player.barcode.clone();
+    add_player_data(&file_path, player).await.map_err(AppError::from)?;
+    emit_roster_changed(&app, &file_path, &barcode);
+    record_session(&app.state::<Mutex<SessionStats>>(), |stats| stats.added += 1);
+    Ok(())
+}
+
+// Start a sibling's entry from an existing player's order details
+#[tauri::command]
+async fn duplicate_player(
+    app: tauri::AppHandle,
+    file_path: String,
+    barcode: String,
+    new_barcode: String,
+) -> Result<Player, AppError> {
+    ensure_writable()?;
+    let player = duplicate_player_data(&file_path, &barcode, &new_barcode)
+        .await
+        .map_err(AppError::from)?;
+    emit_roster_changed(&app, &file_path, &player.barcode);
+    record_session(&app.state::<Mutex<SessionStats>>(), |stats| stats.added += 1);
+    Ok(player)
+}
+
+#[tauri::command]
+async fn create_backup(file_path: String) -> Result<String, AppError> {
+    ensure_writable()?;
+    create_backup_file(&file_path).await.map_err(AppError::from)
+}
+
+// End-of-event bundle: the roster and all its backups copied into archive/<date>/
+// beside it. Returns the archive folder
+#[tauri::command]
+async fn archive_roster(file_path: String) -> Result<String, AppError> {
+    ensure_writable()?;
+    let date = chrono::Local::now().date_naive();
+    archive_roster_files(&file_path, date).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn write_csv_file(
+    file_path: String,
+    csv_content: String,
+    directory: Option<String>,
+) -> Result<WriteResult, AppError> {
+    ensure_writable()?;
+    write_csv_content(&file_path, csv_content, directory.as_deref())
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn export_team(file_path: String, team: String, out_path: String) -> Result<WriteResult, AppError> {
+    ensure_writable()?;
+    export_team_data(&file_path, &team, &out_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn export_labels(file_path: String, out_path: String) -> Result<WriteResult, AppError> {
+    ensure_writable()?;
+    export_labels_data(&file_path, &out_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn export_json(file_path: String, out_path: String) -> Result<WriteResult, AppError> {
+    ensure_writable()?;
+    export_json_data(&file_path, &out_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn import_json(csv_path: String, json_path: String) -> Result<ImportResult, AppError> {
+    ensure_writable()?;
+    import_json_data(&csv_path, &json_path).await.map_err(AppError::from)
+}
+
+// Apply a reissued-labels file (old barcode, new barcode per row) to the roster
+#[tauri::command]
+async fn remap_barcodes(file_path: String, mapping_path: String) -> Result<RemapResult, AppError> {
+    ensure_writable()?;
+    remap_barcodes_data(&file_path, &mapping_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn merge_rosters(base_path: String, incoming_path: String) -> Result<MergeResult, AppError> {
+    ensure_writable()?;
+    merge_rosters_data(&base_path, &incoming_path).await.map_err(AppError::from)
+}
+
+// Clean up team spellings so "U10 Red " and "U10  Red" stop showing as two teams.
+// Title-casing is opt-in since some leagues write names like "U10 RED" on purpose
+#[tauri::command]
+async fn normalize_teams(file_path: String, title_case: Option<bool>) -> Result<Vec<TeamMerge>, AppError> {
+    ensure_writable()?;
+    normalize_teams_data(&file_path, title_case.unwrap_or(false))
+        .await
+        .map_err(AppError::from)
+}
+
+// Players with a field that looks double-encoded ("JosÃ©" for "José"), for review
+// before fix_encoding rewrites them
+#[tauri::command]
+async fn detect_encoding_issues(file_path: String) -> Result<Vec<Player>, AppError> {
+    let csv_data = load_csv_file(&file_path).await.map_err(AppError::from)?;
+    Ok(csv_data
+        .players
+        .into_iter()
+        .filter(|player| !encoding_fixes(player, &csv_data.headers).is_empty())
+        .collect())
+}
+
+// Repair what detect_encoding_issues finds; returns the players as saved
+#[tauri::command]
+async fn fix_encoding(file_path: String) -> Result<Vec<Player>, AppError> {
+    ensure_writable()?;
+    fix_encoding_data(&file_path).await.map_err(AppError::from)
+}
+
+// Compare two snapshots (usually backups) of a roster; path_a is treated as the older one
+#[tauri::command]
+async fn diff_backups(path_a: String, path_b: String) -> Result<Vec<PlayerDiff>, AppError> {
+    let before = load_csv_file(&path_a).await.map_err(AppError::from)?;
+    let after = load_csv_file(&path_b).await.map_err(AppError::from)?;
+    Ok(diff_players(&before.players, &after.players))
+}
+
+#[tauri::command]
+async fn list_backups(file_path: String) -> Result<Vec<BackupInfo>, AppError> {
+    list_backup_files(&file_path).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn backup_disk_usage(file_path: String) -> Result<BackupUsage, AppError> {
+    backup_usage(&file_path).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn restore_backup(backup_path: String, target_path: String) -> Result<(), AppError> {
+    ensure_writable()?;
+    restore_backup_file(&backup_path, &target_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn restore_last_good(file_path: String) -> Result<(), AppError> {
+    ensure_writable()?;
+    restore_last_good_file(&file_path).await.map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_backup_dir(path: String) -> Result<(), AppError> {
+    let path = path.trim().to_string();
+    update_config(|settings| {
+        // An empty path restores the default of backing up next to the file
+        settings.backup_dir = if path.is_empty() { None } else { Some(path) };
+    })
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn get_config() -> Result<Config, AppError> {
+    Ok(config())
+}
+
+// Replace the whole config at once, checked the same way the single-setting commands check
+#[tauri::command]
+fn set_config(new_config: Config) -> Result<(), AppError> {
+    validate_folder_name(&new_config.git_repo_folder)?;
+    validate_folder_name(&new_config.app_repo_folder)?;
+    if let Some(email) = &new_config.git_user_email {
+        validate_email(email).map_err(AppError::validation)?;
+    }
+    let level = new_config
+        .log_level
+        .parse::<log::LevelFilter>()
+        .map_err(|_| AppError::validation(format!("Unknown log level: \"{}\"", new_config.log_level)))?;
+
+    update_config(|current| *current = new_config).map_err(AppError::from)?;
+    log::set_max_level(level);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_read_only(enabled: bool) -> Result<(), AppError> {
+    update_config(|settings| settings.read_only = enabled).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn is_read_only() -> Result<bool, AppError> {
+    Ok(config().read_only)
+}
+
+fn ensure_writable() -> Result<(), AppError> {
+    ensure_writable_with(&config())
+}
+
+fn ensure_writable_with(settings: &Config) -> Result<(), AppError> {
+    if settings.read_only {
+        return Err(AppError::read_only("This station is in read-only mode; changes can't be saved"));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn set_max_backups(count: usize) -> Result<(), AppError> {
+    update_config(|settings| settings.max_backups = count).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_git_repo(url: String, folder: String) -> Result<(), AppError> {
+    let url = url.trim().to_string();
+    let folder = folder.trim().to_string();
+    validate_folder_name(&folder)?;
+
+    // Blank values fall back to the original barcodes repo
+    update_config(|settings| {
+        settings.git_repo_url = if url.is_empty() { DEFAULT_GIT_REPO_URL.to_string() } else { url };
+        settings.git_repo_folder = if folder.is_empty() {
+            DEFAULT_GIT_REPO_FOLDER.to_string()
+        } else {
+            folder
+        };
+    })
+    .map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_app_repo_folder(folder: String) -> Result<(), AppError> {
+    let folder = folder.trim().to_string();
+    validate_folder_name(&folder)?;
+
+    update_config(|settings| {
+        settings.app_repo_folder = if folder.is_empty() {
+            DEFAULT_APP_REPO_FOLDER.to_string()
+        } else {
+            folder
+        };
+    })
+    .map_err(AppError::from)
+}
+
+// Sister folders are looked up next to the app, so only a bare name makes sense
+fn validate_folder_name(folder: &str) -> Result<(), AppError> {
+    if folder.contains(['/', '\\']) || folder == ".." {
+        return Err(AppError::validation("The repository folder must be a plain folder name"));
+    }
+    Ok(())
+}
+
+// Where support staff can find the log; rotated files sit alongside it
+#[tauri::command]
+fn get_log_path(app: tauri::AppHandle) -> Result<String, AppError> {
+    let log_dir = app.path().app_log_dir().map_err(|e| AppError::io(e.to_string()))?;
+    Ok(log_dir.join(format!("{}.log", LOG_FILE_NAME)).to_string_lossy().to_string())
+}
+
+// Open the roster's folder in Finder/Explorer/the file manager, with the file selected
+#[tauri::command]
+fn reveal_in_folder(app: tauri::AppHandle, file_path: String) -> Result<(), AppError> {
+    let path = Path::new(&file_path);
+    if !path.exists() {
+        return Err(AppError::file_not_found(format!("File not found: {}", file_path)));
+    }
+    app.opener()
+        .reveal_item_in_dir(path)
+        .map_err(|e| AppError::io(format!("Couldn't open the folder: {}", e)))
+}
+
+// Open the player's photo folder, creating it on first use so photos can be
+// dropped straight in
+#[tauri::command]
+fn open_player_photos(app: tauri::AppHandle, barcode: String) -> Result<(), AppError> {
+    let dir = player_photos_dir(&get_parent_dir()?, &barcode)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(format!("Couldn't create photo folder {}: {}", dir.display(), e)))?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| AppError::io(format!("Couldn't open the photo folder: {}", e)))
+}
+
+#[tauri::command]
+fn set_log_level(level: String) -> Result<(), AppError> {
+    let filter = level
+        .trim()
+        .parse::<log::LevelFilter>()
+        .map_err(|_| AppError::validation(format!("Unknown log level: \"{}\"", level.trim())))?;
+    log::set_max_level(filter);
+
+    update_config(|settings| settings.log_level = filter.to_string().to_lowercase()).map_err(AppError::from)
+}
+
+#[tauri::command]
+fn set_git_identity(name: String, email: String) -> Result<(), AppError> {
+    let name = name.trim().to_string();
+    let email = email.trim().to_string();
+    validate_email(&email).map_err(AppError::validation)?;
+
+    update_config(|settings| {
+        settings.git_user_name = if name.is_empty() { None } else { Some(name) };
+        settings.git_user_email = if email.is_empty() { None } else { Some(email) };
+    })
+    .map_err(AppError::from)
+}
+
+// Version from tauri.conf.json, for showing alongside check_for_update
+#[tauri::command]
+fn app_version(app: tauri::AppHandle) -> String {
+    app.package_info().version.to_string()
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub available: bool,
+    // Commits on the remote not yet in the local app repo
+    pub behind: usize,
+    // Subject of the newest remote commit, when an update is available
+    pub latest_summary: Option<String>,
+}
+
+// Ask the app repo's remote whether there's anything new, without running the update
+#[tauri::command]
+fn check_for_update() -> Result<UpdateInfo, AppError> {
+    ensure_git_installed()?;
+    let repo_dir = get_app_repo_dir()?;
+
+    if !repo_dir.join(".git").exists() {
+        return Err(AppError::file_not_found(format!(
+            "App repository not found at: {}",
+            repo_dir.display()
+        )));
+    }
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&repo_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git {}: {}", args[0], e)))
+    };
+
+    let fetch_output = git(&["fetch", "--quiet"])?;
+    if !fetch_output.status.success() {
+        let stderr = String::from_utf8_lossy(&fetch_output.stderr);
+        return Err(AppError::git(format!("Git fetch failed: {}", stderr)));
+    }
+
+    let count_output = git(&["rev-list", "--count", "HEAD..@{u}"])?;
+    if !count_output.status.success() {
+        let stderr = String::from_utf8_lossy(&count_output.stderr);
+        return Err(AppError::git(format!("Git rev-list failed: {}", stderr)));
+    }
+    let behind = String::from_utf8_lossy(&count_output.stdout)
+        .trim()
+        .parse::<usize>()
+        .unwrap_or(0);
+
+    let latest_summary = if behind > 0 {
+        let log_output = git(&["log", "-1", "--format=%s", "@{u}"])?;
+        Some(String::from_utf8_lossy(&log_output.stdout).trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(UpdateInfo {
+        available: behind > 0,
+        behind,
+        latest_summary,
+    })
+}
+
+// The repo is a sibling of the installed app (AppImage, .app bundle or .exe)
+fn find_update_script() -> Result<PathBuf, AppError> {
+    let repo_dir = get_app_repo_dir()?;
+
+    let update_script = if cfg!(target_os = "windows") {
+        repo_dir.join("update.bat")
+    } else {
+        repo_dir.join("update.sh")
+    };
+
+    if !update_script.exists() {
+        return Err(AppError::file_not_found(format!(
+            "Update script not found at: {}",
+            update_script.display()
+        )));
+    }
+
+    Ok(update_script)
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateOutput {
+    stream: &'static str,
+    line: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateFinished {
+    success: bool,
+    code: Option<i32>,
+}
+
+// Run update.sh in the background and stream its output as `update-output` events,
+// ending with `update-finished`. Works without a terminal emulator installed.
+#[tauri::command]
+fn run_update_captured(app: tauri::AppHandle) -> Result<(), AppError> {
+    // The installer replaces the running exe, which Windows won't allow from inside the app
+    if cfg!(target_os = "windows") {
+        return Err(AppError::io("In-app updates aren't supported on Windows"));
+    }
+
+    let update_script = find_update_script()?;
+    let repo_dir = update_script.parent().unwrap_or(Path::new("."));
+
+    let mut child = Command::new("bash")
+        .arg(&update_script)
+        .current_dir(repo_dir)
+        // The script's "Press Enter" prompts return immediately with no stdin
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+
+    let forward = |stream: &'static str, reader: Box<dyn std::io::Read + Send>| {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            use std::io::BufRead;
+            for line in std::io::BufReader::new(reader).lines().map_while(Result::ok) {
+                let _ = app.emit("update-output", UpdateOutput { stream, line });
+            }
+        })
+    };
+    let readers = [
+        child.stdout.take().map(|out| forward("stdout", Box::new(out))),
+        child.stderr.take().map(|err| forward("stderr", Box::new(err))),
+    ];
+
+    std::thread::spawn(move || {
+        let status = child.wait();
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.join();
+        }
+        let finished = match status {
+            Ok(status) => UpdateFinished {
+                success: status.success(),
+                code: status.code(),
+            },
+            Err(_) => UpdateFinished {
+                success: false,
+                code: None,
+            },
+        };
+        let _ = app.emit("update-finished", finished);
+    });
+
+    Ok(())
+}
+
+// Fallback for when the captured run can't be used: open a terminal for the script
+#[tauri::command]
+fn run_update() -> Result<(), AppError> {
+    let update_script = find_update_script()?;
+
+    // Open a terminal and run the update script
+    #[cfg(target_os = "linux")]
+    {
+        // Try common Linux terminal emulators
+        let terminals = [
+            ("gnome-terminal", vec!["--", "bash", "-c"]),
+            ("konsole", vec!["-e", "bash", "-c"]),
+            ("xfce4-terminal", vec!["-e"]),
+            ("xterm", vec!["-e"]),
+        ];
+
+        for (term, args) in terminals.iter() {
+            let mut cmd = Command::new(term);
+            for arg in args {
+                cmd.arg(arg);
+            }
+            cmd.arg(update_script.to_str().unwrap());
+
+            if cmd.spawn().is_ok() {
+                return Ok(());
+            }
+        }
+
+        return Err(AppError::io("Could not find a terminal emulator"));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open")
+            .arg("-a")
+            .arg("Terminal")
+            .arg(&update_script)
+            .spawn()?;
+        return Ok(());
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // `start` gives the script its own console window, like the terminal on Linux/macOS
+        Command::new("cmd")
+            .args(["/C", "start", "MVS Update", "cmd", "/K"])
+            .arg(&update_script)
+            .current_dir(update_script.parent().unwrap_or(Path::new(".")))
+            .spawn()?;
+        return Ok(());
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+    {
+        return Err(AppError::io("Unsupported operating system"));
+    }
+}
+
+// Parsed rosters keyed by path, with the modification time and size they were read at
+// A static rather than tauri::State: the file helpers that read and invalidate it
+// run below the command layer and have no AppHandle to reach managed state through
+static CSV_CACHE: LazyLock<Mutex<HashMap<PathBuf, (FileStamp, CSVData)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+type FileStamp = (std::time::SystemTime, u64);
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+// Drop a cached roster; called on every write since coarse mtimes (FAT, SMB)
+// can miss a rewrite that lands within the same tick
+fn invalidate_cached_csv(path: &Path) {
+    CSV_CACHE.lock().unwrap().remove(path);
+}
+
+// Serve repeat reads of an unchanged file from memory; slow network shares
+// otherwise add a full re-read and re-parse to every command
+async fn load_csv_file(file_path: &str) -> AnyhowResult<CSVData> {
+    load_csv_file_reporting(file_path, &mut |_, _| {})
+}
+
+// Load for callers outside the app, like the validate-roster binary; together
+// with validate_players and summarize_roster it runs the same checks as the
+// commands. It calls the synchronous loader directly, so no window and no async
+// runtime are involved
+pub fn load_roster(file_path: &str) -> Result<CSVData, AppError> {
+    load_csv_file_reporting(file_path, &mut |_, _| {}).map_err(AppError::from)
+}
+
+// Rows parsed between progress callbacks
+const PROGRESS_INTERVAL: usize = 250;
+
+// `on_progress` gets (rows parsed, estimated total rows) while a file is parsed;
+// a cache hit returns before any rows are reported. Plain blocking code; the
+// async callers just run it
+fn load_csv_file_reporting(
+    file_path: &str,
+    on_progress: &mut (dyn FnMut(usize, usize) + Send),
+) -> AnyhowResult<CSVData> {
+    let path = Path::new(file_path);
+    let stamp = file_stamp(path);
+    if let Some(stamp) = stamp {
+        if let Some((cached_stamp, csv_data)) = CSV_CACHE.lock().unwrap().get(path) {
+            if *cached_stamp == stamp {
+                return Ok(csv_data.clone());
+            }
+        }
+    }
+
+    let csv_data = read_csv_file(file_path, on_progress)?;
+    if let Some(stamp) = stamp {
+        CSV_CACHE
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (stamp, csv_data.clone()));
+    }
+    Ok(csv_data)
+}
+
+fn read_csv_file(file_path: &str, on_progress: &mut (dyn FnMut(usize, usize) + Send)) -> AnyhowResult<CSVData> {
+    if is_xlsx(file_path) {
+        return load_xlsx_file(file_path, on_progress);
+    }
+
+    let mut bytes = fs::read(file_path).with_context(|| format!("Failed to read file: {}", file_path))?;
+    if is_gzip(file_path) {
+        bytes = gunzip(&bytes).with_context(|| format!("Failed to decompress {}", file_path))?;
+    }
+    let content = String::from_utf8(bytes)
+        .map_err(|_| not_a_csv_error("it isn't a text file"))?;
+
+    match load_dialect(file_path)? {
+        Some(dialect) => parse_with_dialect(&content, file_path, &dialect, on_progress),
+        None => parse_csv_content(&content, file_path, on_progress),
+    }
+}
+
+// A broken dialect file is reported rather than ignored; silently falling back to
+// detection would misread exactly the files it exists for
+fn load_dialect(file_path: &str) -> AnyhowResult<Option<CsvDialect>> {
+    let Some(dir) = Path::new(file_path).parent() else {
+        return Ok(None);
+    };
+    let path = dir.join(DIALECT_FILE_NAME);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(None);
+    };
+
+    let dialect: CsvDialect = serde_json::from_str(&content)
+        .map_err(|e| AppError::parse(format!("Invalid {}: {}", path.display(), e)))?;
+    for c in [dialect.delimiter, dialect.quote].into_iter().flatten() {
+        if !c.is_ascii() || c == '\n' || c == '\r' {
+            return Err(AppError::parse(format!(
+                "Invalid {}: '{}' can't be used as a delimiter or quote",
+                path.display(),
+                c.escape_default()
+            ))
+            .into());
+        }
+    }
+
+    Ok(Some(dialect))
+}
+
+fn is_xlsx(file_path: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("xlsx"))
+}
+
+// Past seasons are archived as .csv.gz on the share
+fn is_gzip(file_path: &str) -> bool {
+    Path::new(file_path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
+}
+
+fn gunzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(bytes), &mut content)?;
+    Ok(content)
+}
+
+// Vendor exports sometimes arrive as Excel workbooks; read the first sheet
+fn load_xlsx_file(file_path: &str, on_progress: &mut (dyn FnMut(usize, usize) + Send)) -> AnyhowResult<CSVData> {
+    let mut workbook: calamine::Xlsx<_> = calamine::open_workbook(file_path)
+        .with_context(|| format!("Failed to open workbook: {}", file_path))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .with_context(|| format!("Workbook has no sheets: {}", file_path))?
+        .with_context(|| format!("Failed to read first sheet of {}", file_path))?;
+
+    parse_xlsx_range(&range, file_path, on_progress)
+}
+
+// Re-encode the sheet as CSV so it goes through the same header checks and row mapping
+fn parse_xlsx_range(
+    range: &calamine::Range<calamine::Data>,
+    file_path: &str,
+    on_progress: &mut (dyn FnMut(usize, usize) + Send),
+) -> AnyhowResult<CSVData> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in range.rows() {
+        // Formatting leaves trailing blank rows in the used range
+        if row.iter().all(|cell| cell.is_empty()) {
+            continue;
+        }
+        writer.write_record(row.iter().map(|cell| cell.to_string()))?;
+    }
+    let content = String::from_utf8(writer.into_inner()?)?;
+
+    parse_delimited(&content, b',', &CsvDialect::default(), file_path, on_progress)
+}
+
+fn parse_csv_content(
+    content: &str,
+    file_path: &str,
+    on_progress: &mut (dyn FnMut(usize, usize) + Send),
+) -> AnyhowResult<CSVData> {
+    // Excel on Windows prefixes UTF-8 exports with a BOM, which would otherwise
+    // end up glued to the first header name
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    // Catch a JPEG or PDF picked by mistake before it turns into bogus players
+    let header = content.lines().next().unwrap_or("");
+    if header.trim().is_empty() {
+        return Err(not_a_csv_error("the first line is empty").into());
+    }
+    if header.chars().any(|c| c.is_control() && c != '\t' && c != '\r') {
+        return Err(not_a_csv_error("it contains binary data").into());
+    }
+    if !header.bytes().any(|b| DELIMITER_CANDIDATES.contains(&b)) {
+        return Err(not_a_csv_error("the first line isn't a delimited header row").into());
+    }
+
+    parse_delimited(content, detect_delimiter(content), &CsvDialect::default(), file_path, on_progress)
+}
+
+// Same as parse_csv_content, but the dialect file's settings win over sniffing
+fn parse_with_dialect(
+    content: &str,
+    file_path: &str,
+    dialect: &CsvDialect,
+    on_progress: &mut (dyn FnMut(usize, usize) + Send),
+) -> AnyhowResult<CSVData> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+
+    let first = content.lines().next().unwrap_or("");
+    if first.trim().is_empty() {
+        return Err(not_a_csv_error("the first line is empty").into());
+    }
+    if first.chars().any(|c| c.is_control() && c != '\t' && c != '\r') {
+        return Err(not_a_csv_error("it contains binary data").into());
+    }
+
+    let delimiter = dialect.delimiter.map_or_else(|| detect_delimiter(content), |d| d as u8);
+    let mut csv_data = parse_delimited(content, delimiter, dialect, file_path, on_progress)?;
+    csv_data.dialect = Some(dialect.clone());
+    Ok(csv_data)
+}
+
+fn not_a_csv_error(reason: &str) -> AppError {
+    AppError::parse(format!("This file doesn't appear to be a CSV ({})", reason))
+}
+
+fn parse_delimited(
+    content: &str,
+    delimiter: u8,
+    dialect: &CsvDialect,
+    file_path: &str,
+    on_progress: &mut (dyn FnMut(usize, usize) + Send),
+) -> AnyhowResult<CSVData> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .quote(dialect.quote_byte())
+        .has_headers(dialect.has_headers)
+        .from_reader(content.as_bytes());
+
+    let headers = if dialect.has_headers {
+        reader.headers()?.clone()
+    } else if dialect.columns.is_empty() {
+        csv::StringRecord::from(PLAYER_COLUMNS.to_vec())
+    } else {
+        csv::StringRecord::from(dialect.columns.clone())
+    };
+    let missing: Vec<String> = REQUIRED_HEADERS
+        .iter()
+        .filter(|required| !headers.iter().any(|h| h == **required))
+        .map(|required| required.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(MissingHeadersError { missing }.into());
+    }
+
+    let mut players = Vec::new();
+    let mut skipped_rows = Vec::new();
+    let mut seen_barcodes = std::collections::HashSet::new();
+    let mut duplicates = Vec::new();
+
+    // Line count only estimates the row total since quoted fields may span lines
+    let total = content.lines().count().saturating_sub(usize::from(dialect.has_headers));
+
+    // Rows are mapped by hand rather than through serde: deserializing into the
+    // flattened other_fields map would type-infer values and reject numeric extras
+    for (index, result) in reader.records().enumerate() {
+        if index > 0 && index % PROGRESS_INTERVAL == 0 {
+            on_progress(index, total.max(index));
+        }
+        match result {
+            // Spreadsheet exports often end with a row of bare separators; it's not a player
+            Ok(record) if record.iter().all(|field| field.trim().is_empty()) => {}
+            Ok(record) => {
+                let player = Player::from_record(&headers, &record);
+                if !player.barcode.is_empty()
+                    && !seen_barcodes.insert(player.barcode.clone())
+                    && !duplicates.contains(&player.barcode)
+                {
+                    duplicates.push(player.barcode.clone());
+                }
+                players.push(player);
+            }
+            Err(e) => {
+                // Keep going so one bad row doesn't hide the rest of the roster
+                let line = e
+                    .position()
+                    .map(|p| p.line() as usize)
+                    .unwrap_or(index + 1 + usize::from(dialect.has_headers));
+                skipped_rows.push(RowError {
+                    line,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+
+    let teams = collect_teams(&players);
+    Ok(CSVData {
+        players,
+        teams,
+        file_path: file_path.to_string(),
+        headers: headers.iter().map(String::from).collect(),
+        delimiter: delimiter as char,
+        quote_style: detect_quote_style(content, delimiter, dialect.quote_byte()),
+        line_ending: detect_line_ending(content),
+        dialect: None,
+        skipped_rows,
+        duplicates,
+    })
+}
+
+// Quote-everything exporters quote the header and every data field, empty ones included
+fn detect_quote_style(content: &str, delimiter: u8, quote: u8) -> QuoteStyle {
+    let all_quoted = |line: &str| {
+        let mut fields = Vec::new();
+        let mut start = 0;
+        let mut in_quotes = false;
+        for (i, byte) in line.bytes().enumerate() {
+            if byte == quote {
+                in_quotes = !in_quotes;
+            } else if byte == delimiter && !in_quotes {
+                fields.push(&line[start..i]);
+                start = i + 1;
+            }
+        }
+        fields.push(&line[start..]);
+        fields.iter().all(|field| {
+            let field = field.trim_end_matches('\r');
+            field.len() >= 2 && field.as_bytes()[0] == quote && field.as_bytes()[field.len() - 1] == quote
+        })
+    };
+
+    let mut lines = content.lines().filter(|line| !line.is_empty()).take(2);
+    match (lines.next(), lines.next()) {
+        (Some(header), first) if all_quoted(header) && first.is_none_or(all_quoted) => QuoteStyle::Always,
+        _ => QuoteStyle::Necessary,
+    }
+}
+
+// Ties (and files with a single unterminated line) keep the LF default
+fn detect_line_ending(content: &str) -> LineEnding {
+    let mut segments: Vec<&str> = content.split('\n').collect();
+    segments.pop();
+    let crlf = segments.iter().filter(|line| line.ends_with('\r')).count();
+    if crlf * 2 > segments.len() {
+        LineEnding::Crlf
+    } else {
+        LineEnding::Lf
+    }
+}
+
+// Distinct team names, sorted
+fn collect_teams(players: &[Player]) -> Vec<String> {
+    let teams: std::collections::BTreeSet<&str> = players.iter().map(|p| p.team.as_str()).collect();
+    teams.into_iter().map(String::from).collect()
+}
+
+// Separators we accept, in order of preference when the header counts tie
+const DELIMITER_CANDIDATES: [u8; 4] = [b',', b'\t', b';', b'|'];
+
+// Sniff the header line for the most frequent unquoted separator, defaulting to comma
+fn detect_delimiter(content: &str) -> u8 {
+    let header = content.lines().next().unwrap_or("");
+
+    let mut best = b',';
+    let mut best_count = 0;
+    for &candidate in DELIMITER_CANDIDATES.iter() {
+        let mut in_quotes = false;
+        let mut count = 0;
+        for byte in header.bytes() {
+            if byte == b'"' {
+                in_quotes = !in_quotes;
+            } else if byte == candidate && !in_quotes {
+                count += 1;
+            }
+        }
+        if count > best_count {
+            best = candidate;
+            best_count = count;
+        }
+    }
+
+    best
+}
+
+// One async lock per roster file, held across load-modify-write so a double-clicked
+// save can't read the file before the first save has written it back. Like
+// CSV_CACHE this is a static rather than tauri::State, since the helpers that take
+// the locks don't have an AppHandle
+static FILE_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<tauri::async_runtime::Mutex<()>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn file_lock(path: &Path) -> Arc<tauri::async_runtime::Mutex<()>> {
+    FILE_LOCKS.lock().unwrap().entry(path.to_path_buf()).or_default().clone()
+}
+
+async fn save_player_data(file_path: &str, player_update: PlayerUpdate) -> AnyhowResult<SaveResult> {
+    save_player_data_with(file_path, player_update, &config()).await
+}
+
+async fn save_player_data_with(
+    file_path: &str,
+    mut player_update: PlayerUpdate,
+    settings: &Config,
+) -> AnyhowResult<SaveResult> {
+    // Reject bad input before touching the file
+    clean_player_update(&mut player_update)?;
+
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+
+    // Load current data
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+    let (previous, saved) = apply_player_update(&mut csv_data, player_update, settings)?;
+
+    // Only back up and rewrite when the edit actually changes the file
+    let content = render_csv_data(&csv_data)?;
+    let changed = content_differs(Path::new(file_path), &content);
+    if changed {
+        create_backup_file(file_path).await?;
+        write_atomic(Path::new(file_path), &content)
+            .with_context(|| format!("Failed to write CSV file: {}", file_path))?;
+        refresh_last_good(Path::new(file_path), &content);
+
+        // The save already landed; a log failure shouldn't report it as failed
+        if let Err(e) = append_edit_log(Path::new(file_path), &edit_log_entries(&previous, &saved)) {
+            log::warn!("Failed to append to edit log: {}", e);
+        }
+        push_undo(file_path, previous);
+    }
+
+    Ok(SaveResult {
+        player: saved,
+        teams: csv_data.teams,
+        changed,
+    })
+}
+
+// Every draft in one scratch file in the app data dir, keyed by barcode
+const DRAFTS_FILE_NAME: &str = "drafts.json";
+
+fn drafts_path(app: &tauri::AppHandle) -> Result<PathBuf, AppError> {
+    let data_dir = app.path().app_data_dir().map_err(|e| AppError::io(e.to_string()))?;
+    Ok(data_dir.join(DRAFTS_FILE_NAME))
+}
+
+fn read_drafts(path: &Path) -> AnyhowResult<std::collections::BTreeMap<String, PlayerUpdate>> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| AppError::parse(format!("Invalid drafts file {}: {}", path.display(), e)).into()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(std::collections::BTreeMap::new()),
+        Err(e) => Err(anyhow::Error::new(e).context(format!("Failed to read {}", path.display()))),
+    }
+}
+
+fn write_drafts(path: &Path, drafts: &std::collections::BTreeMap<String, PlayerUpdate>) -> AnyhowResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, serde_json::to_string_pretty(drafts)?.as_bytes())
+        .with_context(|| format!("Failed to save drafts to {}", path.display()))
+}
+
+async fn store_draft(path: &Path, draft: PlayerUpdate) -> AnyhowResult<()> {
+    let barcode = draft.barcode.trim().to_string();
+    if barcode.is_empty() {
+        return Err(AppError::validation("A draft needs the player's barcode").into());
+    }
+
+    let lock = file_lock(path);
+    let _guard = lock.lock().await;
+    let mut drafts = read_drafts(path)?;
+    drafts.insert(barcode, draft);
+    write_drafts(path, &drafts)
+}
+
+async fn remove_draft(path: &Path, barcode: &str) -> AnyhowResult<()> {
+    let lock = file_lock(path);
+    let _guard = lock.lock().await;
+    let mut drafts = read_drafts(path)?;
+    if drafts.remove(barcode.trim()).is_some() {
+        write_drafts(path, &drafts)?;
+    }
+    Ok(())
+}
+
+// Exactly what save_player_data would write for this update, nothing touched on disk
+async fn preview_save_data(file_path: &str, mut player_update: PlayerUpdate) -> AnyhowResult<String> {
+    clean_player_update(&mut player_update)?;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+    apply_player_update(&mut csv_data, player_update, &config())?;
+    Ok(String::from_utf8(render_csv_data(&csv_data)?)?)
+}
+
+// An existing team the new name is probably a typo of: same apart from case and
+// spacing, or within two edits. Names with different numbers are different age
+// groups ("U10 Red" vs "U12 Red"), so they never match
+fn similar_team<'a>(team: &str, teams: &'a [String]) -> Option<&'a str> {
+    if team.is_empty() || teams.iter().any(|t| t == team) {
+        return None;
+    }
+    let key = |name: &str| name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    let digits = |name: &str| name.chars().filter(char::is_ascii_digit).collect::<String>();
+
+    let wanted = key(team);
+    teams
+        .iter()
+        .filter(|existing| !existing.is_empty() && digits(existing) == digits(team))
+        .map(|existing| (edit_distance(&key(existing), &wanted), existing.as_str()))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, existing)| existing)
+}
+
+// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a_char == *b_char {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+// Lookup predicate for a scanned or typed barcode, honoring case_insensitive_barcodes
+fn matches_barcode(wanted: &str) -> impl Fn(&Player) -> bool + '_ {
+    let ignore_case = config().case_insensitive_barcodes;
+    move |player| same_barcode(&player.barcode, wanted, ignore_case)
+}
+
+// Like matches_barcode, but a player without a barcode can also be found by their
+// fallback_match_column value. Only lookups, saves and undo use this; conflict
+// checks stay on barcodes alone
+fn matches_player_key<'a>(wanted: &'a str, settings: &'a Config) -> impl Fn(&Player) -> bool + 'a {
+    move |player| {
+        same_barcode(&player.barcode, wanted, settings.case_insensitive_barcodes)
+            || settings
+                .fallback_match_column
+                .as_deref()
+                .is_some_and(|column| matches_fallback_key(player, column, wanted))
+    }
+}
+
+// The key matches_player_key finds this row by
+fn player_key<'a>(player: &'a Player, settings: &Config) -> &'a str {
+    match &settings.fallback_match_column {
+        Some(column) if player.barcode.is_empty() => player.value(column),
+        _ => &player.barcode,
+    }
+}
+
+fn matches_fallback_key(player: &Player, column: &str, wanted: &str) -> bool {
+    let wanted = wanted.trim();
+    player.barcode.is_empty()
+        && !wanted.is_empty()
+        && player.other_fields.get(column).is_some_and(|value| value.trim() == wanted)
+}
+
+fn same_barcode(stored: &str, wanted: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        stored.trim().to_uppercase() == wanted.trim().to_uppercase()
+    } else {
+        stored == wanted
+    }
+}
+
+// Trim, validate and normalize an update the way every save does
+fn clean_player_update(player_update: &mut PlayerUpdate) -> AnyhowResult<()> {
+    player_update.trim_fields();
+    validate_email(&player_update.email).map_err(AppError::validation)?;
+    if let Some(column) = player_update.extra_fields.keys().find(|k| PLAYER_COLUMNS.contains(&k.as_str())) {
+        return Err(AppError::validation(format!("\"{}\" can't be set as an extra field", column)).into());
+    }
+    player_update.cell_phone = normalize_phone(&player_update.cell_phone).map_err(AppError::validation)?;
+    Ok(())
+}
+
+// Apply the update to its player in memory, returning the player before and after
+fn apply_player_update(
+    csv_data: &mut CSVData,
+    player_update: PlayerUpdate,
+    settings: &Config,
+) -> AnyhowResult<(Player, Player)> {
+    if let Some(column) = player_update.extra_fields.keys().find(|k| !csv_data.headers.contains(k)) {
+        return Err(AppError::validation(format!("The roster has no \"{}\" column", column)).into());
+    }
+
+    let key = player_update.match_key(settings)?.to_string();
+    let is_match = matches_player_key(&key, settings);
+    let player = csv_data
+        .players
+        .iter_mut()
+        .find(|p| is_match(p))
+        .ok_or_else(|| AppError::not_found(format!("No player with barcode {} found", key)))?;
+    let previous = player.clone();
+    if player_update.team != previous.team && !player_update.confirm_new_team {
+        if let Some(existing) = similar_team(&player_update.team, &csv_data.teams) {
+            return Err(AppError::NeedsConfirmation {
+                message: format!("\"{}\" isn't a team yet. Did you mean \"{}\"?", player_update.team, existing),
+                suggestion: Some(format!(
+                    "Choose \"{}\", or confirm to start \"{}\" as a new team.",
+                    existing, player_update.team
+                )),
+            }
+            .into());
+        }
+    }
+    player.team = player_update.team;
+    player.first_name = player_update.first_name;
+    player.last_name = player_update.last_name;
+    player.jersey_number = player_update.jersey_number;
+    player.cell_phone = player_update.cell_phone;
+    player.email = player_update.email;
+    player.coach = player_update.coach;
+    player.set_value("Products", player_update.products);
+    player.set_value("Packages", player_update.packages);
+    player.other_fields.extend(player_update.extra_fields);
+    let saved = player.clone();
+    csv_data.teams = collect_teams(&csv_data.players);
+
+    Ok((previous, saved))
+}
+
+// Audit trail for a roster, kept beside it as one JSON entry per line
+fn edit_log_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!("{}.edits.jsonl", file_name))
+}
+
+// Fields (typed or extra column) whose value differs between the two states
+fn changed_columns<'a>(before: &'a Player, after: &'a Player) -> Vec<&'a str> {
+    let mut extra_columns: Vec<&str> = after
+        .other_fields
+        .keys()
+        .chain(before.other_fields.keys())
+        .map(String::as_str)
+        .collect();
+    extra_columns.sort();
+    extra_columns.dedup();
+
+    PLAYER_COLUMNS
+        .iter()
+        .copied()
+        .chain(extra_columns)
+        .filter(|column| before.value(column) != after.value(column))
+        .collect()
+}
+
+fn edit_log_entries(before: &Player, after: &Player) -> Vec<EditLogEntry> {
+    let timestamp = Utc::now();
+    changed_columns(before, after)
+        .into_iter()
+        .map(|column| EditLogEntry {
+            timestamp,
+            barcode: after.barcode.clone(),
+            field: column.to_string(),
+            old_value: before.value(column).to_string(),
+            new_value: after.value(column).to_string(),
+        })
+        .collect()
+}
+
+// Changed and removed players in `before` order, then added players in `after` order.
+// Rows without a barcode can't be matched up and are left out
+fn diff_players(before: &[Player], after: &[Player]) -> Vec<PlayerDiff> {
+    let find = |players: &[Player], barcode: &str| players.iter().position(|p| p.barcode == barcode);
+    let mut diffs = Vec::new();
+
+    for old in before.iter().filter(|p| !p.barcode.is_empty()) {
+        match find(after, &old.barcode) {
+            None => diffs.push(PlayerDiff {
+                barcode: old.barcode.clone(),
+                kind: DiffKind::Removed,
+                fields: Vec::new(),
+            }),
+            Some(index) => {
+                let new = &after[index];
+                let fields: Vec<FieldChange> = changed_columns(old, new)
+                    .into_iter()
+                    .map(|column| FieldChange {
+                        field: column.to_string(),
+                        old_value: old.value(column).to_string(),
+                        new_value: new.value(column).to_string(),
+                    })
+                    .collect();
+                if !fields.is_empty() {
+                    diffs.push(PlayerDiff {
+                        barcode: old.barcode.clone(),
+                        kind: DiffKind::Changed,
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+
+    for new in after.iter().filter(|p| !p.barcode.is_empty()) {
+        if find(before, &new.barcode).is_none() {
+            diffs.push(PlayerDiff {
+                barcode: new.barcode.clone(),
+                kind: DiffKind::Added,
+                fields: Vec::new(),
+            });
+        }
+    }
+    diffs
+}
+
+fn append_edit_log(path: &Path, entries: &[EditLogEntry]) -> AnyhowResult<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+
+    let log_path = edit_log_path(path);
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .with_context(|| format!("Failed to open edit log {}", log_path.display()))?;
+    std::io::Write::write_all(&mut file, lines.as_bytes())
+        .with_context(|| format!("Failed to write edit log {}", log_path.display()))?;
+    Ok(())
+}
+
+// Entries oldest first; a roster that was never edited simply has none
+fn read_edit_log(path: &Path) -> AnyhowResult<Vec<EditLogEntry>> {
+    let log_path = edit_log_path(path);
+    let content = match fs::read_to_string(&log_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read edit log {}", log_path.display())),
+    };
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("Malformed edit log entry on line {}", index + 1))
+        })
+        .collect()
+}
+
+// Best matches first across name, jersey number and barcode; ties keep roster
+// order and an empty query returns everyone
+fn rank_players(players: Vec<Player>, query: &str) -> Vec<Player> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return players;
+    }
+
+    let mut scored: Vec<(u32, Player)> = players
+        .into_iter()
+        .filter_map(|player| {
+            let full_name = format!("{} {}", player.first_name, player.last_name);
+            let score = [
+                player.first_name.as_str(),
+                player.last_name.as_str(),
+                full_name.as_str(),
+                player.jersey_number.as_str(),
+                player.barcode.as_str(),
+            ]
+            .iter()
+            .map(|field| match_score(&field.to_lowercase(), &query))
+            .max()
+            .unwrap_or(0);
+            (score > 0).then_some((score, player))
+        })
+        .collect();
+
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, player)| player).collect()
+}
+
+// Exact beats prefix beats substring beats a fuzzy in-order subsequence
+fn match_score(field: &str, query: &str) -> u32 {
+    if field.is_empty() {
+        0
+    } else if field == query {
+        100
+    } else if field.starts_with(query) {
+        75
+    } else if field.contains(query) {
+        50
+    } else if is_subsequence(query, field) {
+        25
+    } else {
+        0
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+// Last name, then first name, ignoring case; a blank name sorts after any
+// filled one. Full ties keep roster order since sort_by is stable
+fn compare_player_names(a: &Player, b: &Player) -> std::cmp::Ordering {
+    let compare = |a: &str, b: &str| {
+        let (a, b) = (a.trim(), b.trim());
+        (a.is_empty(), a.to_lowercase()).cmp(&(b.is_empty(), b.to_lowercase()))
+    };
+    compare(&a.last_name, &b.last_name).then_with(|| compare(&a.first_name, &b.first_name))
+}
+
+// Jersey numbers are free text: numeric ones sort by value ("2" before "10"),
+// a trailing suffix breaks ties ("2" < "2A" < "3"), non-numeric values like "C"
+// follow the numbers alphabetically, and blanks go last
+fn compare_jersey_numbers(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let (a, b) = (a.trim(), b.trim());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+
+    match (split_jersey_number(a), split_jersey_number(b)) {
+        (Some((a_num, a_rest)), Some((b_num, b_rest))) => a_num
+            .cmp(&b_num)
+            .then_with(|| a_rest.to_lowercase().cmp(&b_rest.to_lowercase())),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+    .then_with(|| a.cmp(b))
+}
+
+// "12B" -> (12, "B"); None when the value doesn't start with a digit
+fn split_jersey_number(value: &str) -> Option<(u64, &str)> {
+    let digits_end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let number = value[..digits_end].parse().ok()?;
+    Some((number, &value[digits_end..]))
+}
+
+// Prior player states recorded by save_player, newest last, per roster file
+static UNDO_STACKS: LazyLock<Mutex<HashMap<String, Vec<Player>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+const MAX_UNDO_DEPTH: usize = 50;
+
+fn push_undo(file_path: &str, previous: Player) {
+    let mut stacks = UNDO_STACKS.lock().unwrap();
+    let stack = stacks.entry(file_path.to_string()).or_default();
+    stack.push(previous);
+    if stack.len() > MAX_UNDO_DEPTH {
+        stack.remove(0);
+    }
+}
+
+async fn undo_last_edit_data(file_path: &str) -> AnyhowResult<Player> {
+    undo_last_edit_with(file_path, &config()).await
+}
+
+async fn undo_last_edit_with(file_path: &str, settings: &Config) -> AnyhowResult<Player> {
+    let previous = UNDO_STACKS
+        .lock()
+        .unwrap()
+        .get(file_path)
+        .and_then(|stack| stack.last().cloned())
+        .ok_or_else(|| AppError::not_found("Nothing to undo"))?;
+
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+    // Found the way the save found it, so a row matched on its fallback column
+    // comes back too
+    let key = player_key(&previous, settings).to_string();
+    let is_match = matches_player_key(&key, settings);
+    let player = csv_data
+        .players
+        .iter_mut()
+        .find(|p| is_match(p))
+        .ok_or_else(|| AppError::not_found(format!("Player {} is no longer in the roster", key)))?;
+    *player = previous.clone();
+
+    create_backup_file(file_path).await?;
+    write_csv_data(file_path, &csv_data)?;
+
+    // Only drop the entry once the restore is on disk
+    if let Some(stack) = UNDO_STACKS.lock().unwrap().get_mut(file_path) {
+        stack.pop();
+    }
+
+    Ok(previous)
+}
+
+async fn delete_player_data(file_path: &str, barcode: &str) -> AnyhowResult<()> {
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    let index = csv_data
+        .players
+        .iter()
+        .position(matches_barcode(barcode))
+        .ok_or_else(|| AppError::not_found(format!("No player with barcode {} found", barcode)))?;
+    csv_data.players.remove(index);
+
+    // Back up only once we know there's something to delete
+    create_backup_file(file_path).await?;
+
+    write_csv_data(file_path, &csv_data)
+}
+
+// Write an export next to (never over) the roster it came from, backing up any
+// earlier export it replaces. An unchanged export is left alone
+async fn write_export(file_path: &str, out_path: &str, content: &[u8]) -> AnyhowResult<WriteResult> {
+    let out = Path::new(out_path);
+    if out == Path::new(file_path) {
+        return Err(AppError::validation("Export would overwrite the roster itself; choose another file").into());
+    }
+
+    let changed = content_differs(out, content);
+    if changed {
+        if out.exists() {
+            create_backup_file(out_path).await?;
+        }
+        write_atomic(out, content).with_context(|| format!("Failed to write export: {}", out_path))?;
+    }
+
+    Ok(WriteResult {
+        path: out_path.to_string(),
+        changed,
+    })
+}
+
+// Write one team's players to their own CSV, keeping the roster's headers and separator
+async fn export_team_data(file_path: &str, team: &str, out_path: &str) -> AnyhowResult<WriteResult> {
+    if is_xlsx(out_path) {
+        return Err(AppError::validation("Teams can only be exported as CSV").into());
+    }
+
+    let mut csv_data = load_csv_file(file_path).await?;
+    csv_data.players.retain(|p| p.team == team);
+    if csv_data.players.is_empty() {
+        return Err(AppError::not_found(format!("No players on team {}", team)).into());
+    }
+
+    let content = render_csv_data(&csv_data)?;
+    write_export(file_path, out_path, &content).await
+}
+
+// Column set and order the label printer's template expects
+const LABEL_COLUMNS: [&str; 6] = ["Barcode Number", "Last Name", "First Name", "Jersey Number", "Team", "Packages"];
+
+// One label row per player, in roster order, for the print run
+async fn export_labels_data(file_path: &str, out_path: &str) -> AnyhowResult<WriteResult> {
+    if is_xlsx(out_path) {
+        return Err(AppError::validation("Labels can only be exported as CSV").into());
+    }
+
+    let csv_data = load_csv_file(file_path).await?;
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(LABEL_COLUMNS)?;
+    for player in &csv_data.players {
+        writer.write_record([
+            &player.barcode,
+            &player.last_name,
+            &player.first_name,
+            &player.jersey_number,
+            &player.team,
+            &player.packages,
+        ])?;
+    }
+    let content = writer.into_inner()?;
+    write_export(file_path, out_path, &content).await
+}
+
+// The whole roster as a pretty-printed JSON array of players, for tools that don't read CSV
+async fn export_json_data(file_path: &str, out_path: &str) -> AnyhowResult<WriteResult> {
+    let csv_data = load_csv_file(file_path).await?;
+    let content = serde_json::to_vec_pretty(&csv_data.players)?;
+    write_export(file_path, out_path, &content).await
+}
+
+// Apply players exported by export_json (or the sister tool) back onto the roster by
+// barcode. Fields the roster has no column for become new columns rather than being dropped
+async fn import_json_data(csv_path: &str, json_path: &str) -> AnyhowResult<ImportResult> {
+    let json = fs::read_to_string(json_path).with_context(|| format!("Failed to read {}", json_path))?;
+    let incoming: Vec<Player> = serde_json::from_str(&json)
+        .map_err(|e| AppError::parse(format!("{} is not a list of players: {}", json_path, e)))?;
+    // One bad player stops the whole import before anything is written
+    let incoming = incoming
+        .into_iter()
+        .map(clean_imported_player)
+        .collect::<AnyhowResult<Vec<Player>>>()?;
+    if incoming.iter().any(|p| p.barcode.is_empty()) {
+        return Err(AppError::validation("Every imported player needs a barcode").into());
+    }
+    let ignore_case = config().case_insensitive_barcodes;
+
+    let lock = file_lock(Path::new(csv_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(csv_path).await?;
+
+    let mut result = ImportResult::default();
+    let mut edits = Vec::new();
+    for player in incoming {
+        for column in player.other_fields.keys() {
+            if !csv_data.headers.contains(column) {
+                csv_data.headers.push(column.clone());
+            }
+        }
+
+        let existing = csv_data
+            .players
+            .iter_mut()
+            .find(|p| same_barcode(&p.barcode, &player.barcode, ignore_case));
+        let Some(existing) = existing else {
+            csv_data.players.push(player);
+            result.added += 1;
+            continue;
+        };
+
+        let previous = existing.clone();
+        for column in PLAYER_COLUMNS {
+            existing.set_value(column, player.value(column).to_string());
+        }
+        existing.other_fields.extend(player.other_fields);
+
+        let changes = edit_log_entries(&previous, existing);
+        if changes.is_empty() {
+            result.unchanged += 1;
+        } else {
+            result.updated += 1;
+            edits.extend(changes);
+        }
+    }
+
+    if result.added + result.updated > 0 {
+        csv_data.teams = collect_teams(&csv_data.players);
+        create_backup_file(csv_path).await?;
+        write_csv_data(csv_path, &csv_data)?;
+        if let Err(e) = append_edit_log(Path::new(csv_path), &edits) {
+            log::warn!("Failed to append to edit log: {}", e);
+        }
+        result.changed = true;
+    }
+    Ok(result)
+}
+
+// Trim, check and format an imported player exactly as save_player would, and
+// rebuild the item lists from the raw strings, which are what gets saved
+fn clean_imported_player(player: Player) -> AnyhowResult<Player> {
+    let mut update = PlayerUpdate {
+        barcode: player.barcode,
+        team: player.team,
+        first_name: player.first_name,
+        last_name: player.last_name,
+        jersey_number: player.jersey_number,
+        cell_phone: player.cell_phone,
+        email: player.email,
+        coach: player.coach,
+        products: player.products,
+        packages: player.packages,
+        extra_fields: HashMap::new(),
+        confirm_new_team: false,
+    };
+    if let Err(e) = clean_player_update(&mut update) {
+        let label = if update.barcode.is_empty() { "without a barcode" } else { &update.barcode };
+        return Err(AppError::validation(format!("Imported player {}: {}", label, AppError::from(e))).into());
+    }
+
+    Ok(Player {
+        barcode: update.barcode,
+        team: update.team,
+        first_name: update.first_name,
+        last_name: update.last_name,
+        jersey_number: update.jersey_number,
+        coach: update.coach,
+        cell_phone: update.cell_phone,
+        email: update.email,
+        product_items: split_order_items(&update.products),
+        package_items: split_order_items(&update.packages),
+        products: update.products,
+        packages: update.packages,
+        other_fields: player.other_fields,
+    })
+}
+
+// The mapping file has a header row and the old and new barcodes in its first two
+// columns. Nothing is written if the result would repeat a barcode
+async fn remap_barcodes_data(file_path: &str, mapping_path: &str) -> AnyhowResult<RemapResult> {
+    let mapping = read_barcode_mapping(mapping_path)?;
+
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    let count_barcodes = |players: &[Player]| {
+        let mut counts = HashMap::<String, usize>::new();
+        for player in players.iter().filter(|p| !p.barcode.is_empty()) {
+            *counts.entry(player.barcode.clone()).or_default() += 1;
+        }
+        counts
+    };
+    let counts_before = count_barcodes(&csv_data.players);
+
+    let mut result = RemapResult::default();
+    let mut matched = std::collections::HashSet::new();
+    let mut edits = Vec::new();
+    for player in csv_data.players.iter_mut() {
+        let Some(new_barcode) = mapping.get(&player.barcode) else {
+            result.unmapped_players.push(player.barcode.clone());
+            continue;
+        };
+        matched.insert(player.barcode.clone());
+        if *new_barcode != player.barcode {
+            let previous = player.clone();
+            player.barcode = new_barcode.clone();
+            edits.extend(edit_log_entries(&previous, player));
+            result.remapped += 1;
+        }
+    }
+    result.unmatched_mapping = mapping.keys().filter(|old| !matched.contains(*old)).cloned().collect();
+
+    // A barcode the roster already repeated is reported on load; only refuse
+    // repeats the mapping itself would add
+    let mut counts_after: Vec<(String, usize)> = count_barcodes(&csv_data.players).into_iter().collect();
+    counts_after.sort();
+    if let Some((barcode, _)) = counts_after
+        .iter()
+        .find(|(barcode, count)| *count > counts_before.get(barcode).copied().unwrap_or(0).max(1))
+    {
+        return Err(AppError::validation(format!(
+            "Applying the mapping would give more than one player barcode {}; nothing was changed",
+            barcode
+        ))
+        .into());
+    }
+
+    if result.remapped > 0 {
+        create_backup_file(file_path).await?;
+        write_csv_data(file_path, &csv_data)?;
+        if let Err(e) = append_edit_log(Path::new(file_path), &edits) {
+            log::warn!("Failed to append to edit log: {}", e);
+        }
+        result.changed = true;
+    }
+    Ok(result)
+}
+
+// Old barcode -> new barcode, sorted by old. An old barcode listed twice with
+// different replacements is ambiguous and rejected
+fn read_barcode_mapping(mapping_path: &str) -> AnyhowResult<std::collections::BTreeMap<String, String>> {
+    let content = fs::read_to_string(mapping_path).with_context(|| format!("Failed to read {}", mapping_path))?;
+    let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(detect_delimiter(content))
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut mapping = std::collections::BTreeMap::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let old = record.get(0).unwrap_or("").trim();
+        let new = record.get(1).unwrap_or("").trim();
+        if old.is_empty() && new.is_empty() {
+            continue;
+        }
+        if old.is_empty() || new.is_empty() {
+            return Err(AppError::validation(format!(
+                "Line {} of {} needs both an old and a new barcode",
+                index + 2,
+                mapping_path
+            ))
+            .into());
+        }
+        if let Some(existing) = mapping.insert(old.to_string(), new.to_string()) {
+            if existing != new {
+                return Err(AppError::validation(format!(
+                    "{} maps barcode {} to both {} and {}",
+                    mapping_path, old, existing, new
+                ))
+                .into());
+            }
+        }
+    }
+
+    Ok(mapping)
+}
+
+// Fold a late order file into the base roster. Only new barcodes are added; the
+// written file keeps the base's columns, so incoming-only columns are dropped
+async fn merge_rosters_data(base_path: &str, incoming_path: &str) -> AnyhowResult<MergeResult> {
+    if Path::new(base_path) == Path::new(incoming_path) {
+        return Err(AppError::validation("Can't merge a roster into itself").into());
+    }
+    let incoming = load_csv_file(incoming_path).await?;
+
+    let lock = file_lock(Path::new(base_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(base_path).await?;
+
+    let mut result = MergeResult::default();
+    for player in incoming.players {
+        if player.barcode.is_empty() {
+            result.skipped += 1;
+            continue;
+        }
+        match csv_data.players.iter().find(|p| p.barcode == player.barcode) {
+            None => {
+                csv_data.players.push(player);
+                result.added += 1;
+            }
+            Some(existing) if incoming.headers.iter().all(|h| existing.value(h) == player.value(h)) => {
+                result.skipped += 1;
+            }
+            Some(_) => {
+                if !result.conflicts.contains(&player.barcode) {
+                    result.conflicts.push(player.barcode);
+                }
+            }
+        }
+    }
+
+    if result.added > 0 {
+        csv_data.teams = collect_teams(&csv_data.players);
+        create_backup_file(base_path).await?;
+        write_csv_data(base_path, &csv_data)?;
+        result.changed = true;
+    }
+    Ok(result)
+}
+
+async fn normalize_teams_data(file_path: &str, title_case: bool) -> AnyhowResult<Vec<TeamMerge>> {
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    let mut groups = std::collections::BTreeMap::<String, (std::collections::BTreeSet<String>, usize)>::new();
+    for player in csv_data.players.iter_mut() {
+        let canonical = normalize_team_name(&player.team, title_case);
+        let (variants, players) = groups.entry(canonical.clone()).or_default();
+        variants.insert(player.team.clone());
+        *players += 1;
+        player.team = canonical;
+    }
+
+    // Only names that actually changed are worth showing the operator
+    let merges: Vec<TeamMerge> = groups
+        .into_iter()
+        .filter(|(canonical, (variants, _))| variants.iter().any(|v| v != canonical))
+        .map(|(canonical, (variants, players))| TeamMerge {
+            canonical,
+            variants: variants.into_iter().collect(),
+            players,
+        })
+        .collect();
+
+    if !merges.is_empty() {
+        csv_data.teams = collect_teams(&csv_data.players);
+        create_backup_file(file_path).await?;
+        write_csv_data(file_path, &csv_data)?;
+    }
+    Ok(merges)
+}
+
+async fn fix_encoding_data(file_path: &str) -> AnyhowResult<Vec<Player>> {
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    let mut fixed = Vec::new();
+    let mut edits = Vec::new();
+    for player in csv_data.players.iter_mut() {
+        let fixes = encoding_fixes(player, &csv_data.headers);
+        if fixes.is_empty() {
+            continue;
+        }
+        let previous = player.clone();
+        for (column, value) in fixes {
+            player.set_value(&column, value);
+        }
+        edits.extend(edit_log_entries(&previous, player));
+        fixed.push(player.clone());
+    }
+
+    if !fixed.is_empty() {
+        csv_data.teams = collect_teams(&csv_data.players);
+        create_backup_file(file_path).await?;
+        write_csv_data(file_path, &csv_data)?;
+        if let Err(e) = append_edit_log(Path::new(file_path), &edits) {
+            log::warn!("Failed to append to edit log: {}", e);
+        }
+    }
+    Ok(fixed)
+}
+
+// Column -> repaired value for each of the player's fields that needs one.
+// Barcodes are left alone: they're plain ASCII on every label we print
+fn encoding_fixes(player: &Player, headers: &[String]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|column| column.as_str() != "Barcode Number")
+        .filter_map(|column| Some((column.clone(), fix_mojibake(player.value(column))?)))
+        .collect()
+}
+
+// UTF-8 text that was read as Windows-1252 and saved again. Map every char back
+// to the single byte it was decoded from and re-read those bytes as UTF-8; text
+// that didn't go through that round trip almost never survives it ("José" ends in
+// a lone 0xE9), so anything that fails is left as it is
+fn fix_mojibake(value: &str) -> Option<String> {
+    if value.is_ascii() {
+        return None;
+    }
+    let bytes = value.chars().map(windows_1252_byte).collect::<Option<Vec<u8>>>()?;
+    let repaired = String::from_utf8(bytes).ok()?;
+    (repaired != value).then_some(repaired)
+}
+
+// The byte Windows-1252 decodes to this char, if any. 0x80-0x9F hold punctuation
+// there instead of control codes; the five bytes it leaves undefined pass through
+fn windows_1252_byte(c: char) -> Option<u8> {
+    let byte = match c {
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8A,
+        '‹' => 0x8B,
+        'Œ' => 0x8C,
+        'Ž' => 0x8E,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201C}' => 0x93,
+        '\u{201D}' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9A,
+        '›' => 0x9B,
+        'œ' => 0x9C,
+        'ž' => 0x9E,
+        'Ÿ' => 0x9F,
+        _ => return u8::try_from(u32::from(c)).ok(),
+    };
+    Some(byte)
+}
+
+// Trim and collapse runs of whitespace; title case capitalizes each word and
+// lowercases the rest ("u10 RED" -> "U10 Red")
+fn normalize_team_name(team: &str, title_case: bool) -> String {
+    team.split_whitespace()
+        .map(|word| {
+            if !title_case {
+                return word.to_string();
+            }
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+async fn add_player_data(file_path: &str, mut player: Player) -> AnyhowResult<()> {
+    if player.barcode.trim().is_empty() {
+        return Err(AppError::validation("Cannot add a player without a barcode").into());
+    }
+
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    if csv_data.players.iter().any(matches_barcode(&player.barcode)) {
+        return Err(AppError::validation(format!("A player with barcode {} already exists", player.barcode)).into());
+    }
+
+    create_backup_file(file_path).await?;
+
+    player.product_items = split_order_items(&player.products);
+    player.package_items = split_order_items(&player.packages);
+
+    // write_players lines every row up by column name, so extra columns the
+    // new player doesn't carry are simply written empty
+    csv_data.players.push(player);
+
+    write_csv_data(file_path, &csv_data)
+}
+
+// The copy goes right after the original so siblings stay together in the file
+async fn duplicate_player_data(file_path: &str, barcode: &str, new_barcode: &str) -> AnyhowResult<Player> {
+    let new_barcode = new_barcode.trim();
+    if new_barcode.is_empty() {
+        return Err(AppError::validation("The copy needs its own barcode").into());
+    }
+
+    let lock = file_lock(Path::new(file_path));
+    let _guard = lock.lock().await;
+    let mut csv_data = load_csv_for_rewrite(file_path).await?;
+
+    if csv_data.players.iter().any(matches_barcode(new_barcode)) {
+        return Err(AppError::validation(format!("A player with barcode {} already exists", new_barcode)).into());
+    }
+    let index = csv_data
+        .players
+        .iter()
+        .position(matches_barcode(barcode))
+        .ok_or_else(|| AppError::not_found(format!("No player with barcode {} found", barcode)))?;
+
+    let mut copy = csv_data.players[index].clone();
+    copy.barcode = new_barcode.to_string();
+    csv_data.players.insert(index + 1, copy.clone());
+
+    create_backup_file(file_path).await?;
+    write_csv_data(file_path, &csv_data)?;
+    Ok(copy)
+}
+
+// Load a roster that is about to be rewritten in place
+async fn load_csv_for_rewrite(file_path: &str) -> AnyhowResult<CSVData> {
+    // Writing is CSV-only; saving over a workbook would destroy it
+    if is_xlsx(file_path) {
+        return Err(AppError::validation("Excel files are read-only here. Save the roster as CSV to edit it.").into());
+    }
+    // Saves are written uncompressed, which would leave plain CSV behind a .gz name
+    if is_gzip(file_path) {
+        return Err(AppError::validation("Compressed archives are read-only. Export a copy as CSV to edit it.").into());
+    }
+
+    let csv_data = load_csv_file(file_path).await?;
+
+    // Rewriting would silently drop any rows we couldn't parse
+    if !csv_data.skipped_rows.is_empty() {
+        anyhow::bail!(
+            "Refusing to save: {} row(s) could not be read and would be lost",
+            csv_data.skipped_rows.len()
+        );
+    }
+
+    Ok(csv_data)
+}
+
+fn write_csv_data(file_path: &str, csv_data: &CSVData) -> AnyhowResult<()> {
+    let content = render_csv_data(csv_data)?;
+    write_atomic(Path::new(file_path), &content)
+        .with_context(|| format!("Failed to write CSV file: {}", file_path))?;
+    refresh_last_good(Path::new(file_path), &content);
+    Ok(())
+}
+
+// Write to a sibling temp file and rename it into place, so a crash or a dropped
+// network drive mid-write leaves the old roster intact instead of half a file
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    invalidate_cached_csv(path);
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        std::io::Write::write_all(&mut file, content)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path).map_err(|e| file_in_use_error(e, &file_name, cfg!(windows)))
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+// Replace the OS message for a rename that failed because another program holds
+// the roster open, and pass every other error through unchanged
+fn file_in_use_error(err: std::io::Error, file_name: &str, on_windows: bool) -> std::io::Error {
+    if !is_file_in_use(&err, on_windows) {
+        return err;
+    }
+    std::io::Error::new(
+        err.kind(),
+        format!(
+            "{} is open in another program. Close it there and try again; the file was not changed",
+            file_name
+        ),
+    )
+}
+
+// Windows won't replace a file another program holds open, as Excel does with
+// any CSV it has loaded. Renaming over it usually fails with ERROR_ACCESS_DENIED
+// rather than a sharing violation, so that OS code counts too; an error without
+// an OS code keeps its own message
+fn is_file_in_use(err: &std::io::Error, on_windows: bool) -> bool {
+    const ERROR_ACCESS_DENIED: i32 = 5;
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+    on_windows
+        && matches!(
+            err.raw_os_error(),
+            Some(ERROR_ACCESS_DENIED | ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+}
+
+fn render_csv_data(csv_data: &CSVData) -> AnyhowResult<Vec<u8>> {
+    // Write with the same separator we read the file with
+    let quote_style = match csv_data.quote_style {
+        QuoteStyle::Necessary => csv::QuoteStyle::Necessary,
+        QuoteStyle::Always => csv::QuoteStyle::Always,
+    };
+    let dialect = csv_data.dialect.clone().unwrap_or_default();
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(csv_data.delimiter as u8)
+        .quote(dialect.quote_byte())
+        .quote_style(quote_style)
+        .terminator(csv_data.line_ending.terminator())
+        .from_writer(Vec::new());
+
+    write_players(&mut writer, &csv_data.headers, &csv_data.players, dialect.has_headers)?;
+
+    Ok(writer.into_inner()?)
+}
+
+// True unless the file already holds exactly these bytes
+fn content_differs(path: &Path, content: &[u8]) -> bool {
+    fs::read(path).map(|existing| existing != content).unwrap_or(true)
+}
+
+fn has_no_order(player: &Player) -> bool {
+    player.products.trim().is_empty() && player.packages.trim().is_empty()
+}
+
+// Emails compare case-insensitively and phones by digits, so "(555) 123-4567" and
+// "555.123.4567" land together. A group needs two different barcodes; a row that
+// is simply duplicated is reported as a duplicate on load instead
+fn shared_contacts(players: &[Player]) -> Vec<ContactGroup> {
+    let mut groups = std::collections::BTreeMap::<(ContactKind, String), Vec<&Player>>::new();
+    for player in players {
+        let email = player.email.trim().to_lowercase();
+        if !email.is_empty() {
+            groups.entry((ContactKind::Email, email)).or_default().push(player);
+        }
+        let digits: String = player.cell_phone.chars().filter(|c| c.is_ascii_digit()).collect();
+        if !digits.is_empty() {
+            groups.entry((ContactKind::Phone, digits)).or_default().push(player);
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, members)| members.iter().any(|p| p.barcode != members[0].barcode))
+        .map(|((kind, _), members)| ContactGroup {
+            kind,
+            contact: match kind {
+                ContactKind::Email => members[0].email.trim().to_string(),
+                ContactKind::Phone => members[0].cell_phone.trim().to_string(),
+            },
+            players: members.into_iter().cloned().collect(),
+        })
+        .collect()
+}
+
+// Sorted by team name, like CSVData.teams
+fn count_teams(players: &[Player]) -> Vec<TeamCount> {
+    let mut counts = std::collections::BTreeMap::<&str, usize>::new();
+    for player in players {
+        *counts.entry(&player.team).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(team, players)| TeamCount {
+            team: team.to_string(),
+            players,
+        })
+        .collect()
+}
+
+// Items are counted per unit ordered, so "810T,810T" adds two to 810T
+pub fn summarize_roster(players: &[Player]) -> RosterSummary {
+    let mut summary = RosterSummary {
+        total_players: players.len(),
+        ..RosterSummary::default()
+    };
+
+    for player in players {
+        *summary.players_per_team.entry(player.team.clone()).or_default() += 1;
+        for item in &player.product_items {
+            *summary.product_counts.entry(item.clone()).or_default() += 1;
+        }
+        for item in &player.package_items {
+            *summary.package_counts.entry(item.clone()).or_default() += 1;
+        }
+    }
+
+    summary
+}
+
+// Run the same checks as a single edit over every player, without writing anything
+pub fn validate_players(csv_data: &CSVData) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    for player in &csv_data.players {
+        let mut report = |message: String| {
+            issues.push(ValidationIssue {
+                barcode: player.barcode.clone(),
+                message,
+            })
+        };
+
+        if player.barcode.trim().is_empty() {
+            report(format!(
+                "{} {} has no barcode",
+                player.first_name.trim(),
+                player.last_name.trim()
+            ));
+        }
+        for column in ["First Name", "Last Name", "Team", "Email", "Cell Phone"] {
+            if let Err(message) = check_field(column, player.value(column)) {
+                report(message);
+            }
+        }
+    }
+
+    for barcode in &csv_data.duplicates {
+        issues.push(ValidationIssue {
+            barcode: barcode.clone(),
+            message: format!("Barcode {} appears more than once", barcode),
+        });
+    }
+
+    issues
+}
+
+fn load_schema(schema_path: &str) -> AnyhowResult<RosterSchema> {
+    let content = fs::read_to_string(schema_path).with_context(|| format!("Failed to read {}", schema_path))?;
+    serde_json::from_str(&content).map_err(|e| AppError::parse(format!("Invalid {}: {}", schema_path, e)).into())
+}
+
+// A missing required column is reported once for the file (with no barcode)
+// rather than once per player
+fn validate_with_schema(csv_data: &CSVData, schema: &RosterSchema) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    for (column, rule) in &schema.columns {
+        if rule.required && !csv_data.headers.contains(column) {
+            issues.push(ValidationIssue {
+                barcode: String::new(),
+                message: format!("Missing required column \"{}\"", column),
+            });
+        }
+    }
+
+    for player in &csv_data.players {
+        for (column, rule) in schema.columns.iter().filter(|(column, _)| csv_data.headers.contains(column)) {
+            if let Err(message) = check_column_rule(column, rule, player.value(column)) {
+                issues.push(ValidationIssue {
+                    barcode: player.barcode.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+fn check_column_rule(column: &str, rule: &ColumnRule, value: &str) -> Result<(), String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return if rule.required {
+            Err(format!("Missing {}", column))
+        } else {
+            Ok(())
+        };
+    }
+
+    match rule.field_type {
+        Some(FieldType::Email) => validate_email(value)?,
+        Some(FieldType::Phone) => normalize_phone(value).map(|_| ())?,
+        Some(FieldType::Number) if !value.parse::<f64>().is_ok_and(f64::is_finite) => {
+            return Err(format!("{} must be a number: \"{}\"", column, value));
+        }
+        Some(FieldType::Number) | None => {}
+    }
+
+    if !rule.allowed.is_empty() && !rule.allowed.iter().any(|allowed| allowed.trim().eq_ignore_ascii_case(value)) {
+        return Err(format!(
+            "{} \"{}\" isn't one of: {}",
+            column,
+            value,
+            rule.allowed.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+// Rules for a single column, shared by validate_field and validate_players.
+// Columns without rules always pass
+fn check_field(field: &str, value: &str) -> Result<(), String> {
+    let required = |message: &str| {
+        if value.trim().is_empty() {
+            Err(message.to_string())
+        } else {
+            Ok(())
+        }
+    };
+
+    match field {
+        "Barcode Number" => required("Missing barcode"),
+        "First Name" => required("Missing first name"),
+        "Last Name" => required("Missing last name"),
+        "Team" => required("Not assigned to a team"),
+        "Email" => validate_email(value),
+        "Cell Phone" => normalize_phone(value).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+// Empty is allowed since some orders have no email; otherwise require
+// local@domain.tld with no whitespace and no empty domain labels
+fn validate_email(email: &str) -> Result<(), String> {
+    let email = email.trim();
+    if email.is_empty() {
+        return Ok(());
+    }
+
+    let invalid = || format!("Invalid email address: \"{}\"", email);
+
+    if email.chars().any(char::is_whitespace) {
+        return Err(invalid());
+    }
+
+    let (local, domain) = email.split_once('@').ok_or_else(invalid)?;
+    if local.is_empty() || domain.contains('@') {
+        return Err(invalid());
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 || labels.iter().any(|label| label.is_empty()) {
+        return Err(invalid());
+    }
+
+    Ok(())
+}
+
+// Store 10-digit US numbers as "(555) 123-4567" regardless of how they were typed;
+// a leading country code 1 is dropped and empty values are left alone
+fn normalize_phone(phone: &str) -> Result<String, String> {
+    if phone.trim().is_empty() {
+        return Ok(String::new());
+    }
+
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = match digits.len() {
+        10 => digits.as_str(),
+        11 if digits.starts_with('1') => &digits[1..],
+        _ => {
+            return Err(format!(
+                "Invalid phone number: \"{}\" (expected 10 digits)",
+                phone.trim()
+            ))
+        }
+    };
+
+    Ok(format!("({}) {}-{}", &digits[..3], &digits[3..6], &digits[6..]))
+}
+
+// Item codes in a Products/Packages cell, one entry per unit ordered ("810T,810T,5x7"),
+// matching how the frontend writes them
+const ORDER_ITEM_SEPARATOR: char = ',';
+
+fn split_order_items(value: &str) -> Vec<String> {
+    value
+        .split(ORDER_ITEM_SEPARATOR)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Column names backing the typed Player fields, in the order they're written
+const PLAYER_COLUMNS: [&str; 10] = [
+    "Barcode Number",
+    "Team",
+    "First Name",
+    "Last Name",
+    "Jersey Number",
+    "Coach",
+    "Cell Phone",
+    "Email",
+    "Products",
+    "Packages",
+];
+
+impl Player {
+    fn from_record(headers: &csv::StringRecord, record: &csv::StringRecord) -> Player {
+        let mut player = Player::default();
+        for (column, value) in headers.iter().zip(record.iter()) {
+            // Exports pad names and emails with stray spaces; extra columns stay verbatim
+            let value = if PLAYER_COLUMNS.contains(&column) { value.trim() } else { value };
+            player.set_value(column, value.to_string());
+        }
+        player
+    }
+
+    // Value of a CSV column, whether it's a typed field or one of the extras
+    fn value(&self, column: &str) -> &str {
+        match column {
+            "Barcode Number" => &self.barcode,
+            "Team" => &self.team,
+            "First Name" => &self.first_name,
+            "Last Name" => &self.last_name,
+            "Jersey Number" => &self.jersey_number,
+            "Coach" => &self.coach,
+            "Cell Phone" => &self.cell_phone,
+            "Email" => &self.email,
+            "Products" => &self.products,
+            "Packages" => &self.packages,
+            _ => self.other_fields.get(column).map(String::as_str).unwrap_or(""),
+        }
+    }
+
+    fn set_value(&mut self, column: &str, value: String) {
+        match column {
+            "Barcode Number" => self.barcode = value,
+            "Team" => self.team = value,
+            "First Name" => self.first_name = value,
+            "Last Name" => self.last_name = value,
+            "Jersey Number" => self.jersey_number = value,
+            "Coach" => self.coach = value,
+            "Cell Phone" => self.cell_phone = value,
+            "Email" => self.email = value,
+            "Products" => {
+                self.product_items = split_order_items(&value);
+                self.products = value;
+            }
+            "Packages" => {
+                self.package_items = split_order_items(&value);
+                self.packages = value;
+            }
+            _ => {
+                self.other_fields.insert(column.to_string(), value);
+            }
+        }
+    }
+}
+
+// Columns to write: the file's own header order first, then any typed column the
+// file lacked that now holds data, then extras that only newly added players carry
+fn output_columns<'a>(headers: &'a [String], players: &'a [Player]) -> Vec<&'a str> {
+    let mut columns: Vec<&str> = headers.iter().map(String::as_str).collect();
+
+    for column in PLAYER_COLUMNS {
+        if !columns.contains(&column) && players.iter().any(|p| !p.value(column).is_empty()) {
+            columns.push(column);
+        }
+    }
+
+    let mut new_extras: Vec<&str> = players
+        .iter()
+        .flat_map(|p| p.other_fields.keys().map(String::as_str))
+        .filter(|column| !columns.contains(column))
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    new_extras.sort();
+    columns.extend(new_extras);
+
+    columns
+}
+
+// csv::Writer can't serialize the flattened other_fields map, so rows are written by hand
+fn write_players<W: std::io::Write>(
+    writer: &mut csv::Writer<W>,
+    headers: &[String],
+    players: &[Player],
+    include_header: bool,
+) -> AnyhowResult<()> {
+    let columns = output_columns(headers, players);
+    if include_header {
+        writer.write_record(&columns)?;
+    }
+
+    for player in players {
+        writer.write_record(columns.iter().map(|column| player.value(column)))?;
+    }
+
+    Ok(())
+}
+
+// Put the columns of frontend-rendered CSV back into the order the existing file
+// uses, ending lines the way it does; content already matching is returned untouched
+fn match_existing_layout(content: &str, existing: &Path) -> AnyhowResult<String> {
+    let Ok(existing_content) = fs::read_to_string(existing) else {
+        return Ok(content.to_string());
+    };
+    let existing_content = existing_content.strip_prefix('\u{feff}').unwrap_or(&existing_content);
+    let mut existing_reader = csv::ReaderBuilder::new()
+        .delimiter(detect_delimiter(existing_content))
+        .from_reader(existing_content.as_bytes());
+    let existing_headers: Vec<String> = match existing_reader.headers() {
+        Ok(headers) => headers.iter().map(String::from).collect(),
+        Err(_) => return Ok(content.to_string()),
+    };
+
+    let delimiter = detect_delimiter(content);
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let mut order: Vec<usize> = existing_headers
+        .iter()
+        .filter_map(|h| headers.iter().position(|c| c == h))
+        .collect();
+    let unknown: Vec<usize> = (0..headers.len()).filter(|i| !order.contains(i)).collect();
+    order.extend(unknown);
+    let line_ending = detect_line_ending(existing_content);
+    if order.iter().copied().eq(0..headers.len()) && detect_line_ending(content) == line_ending {
+        return Ok(content.to_string());
+    }
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .terminator(line_ending.terminator())
+        .from_writer(Vec::new());
+    writer.write_record(order.iter().map(|&i| &headers[i]))?;
+    for record in reader.records() {
+        let record = record?;
+        writer.write_record(order.iter().map(|&i| record.get(i).unwrap_or("")))?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+async fn write_csv_content(
+    file_path: &str,
+    csv_content: String,
+    directory: Option<&str>,
+) -> AnyhowResult<WriteResult> {
+    let target_path = resolve_write_target(file_path, directory)?;
+    if is_xlsx(&target_path.to_string_lossy()) {
+        return Err(AppError::validation("Can't write CSV content over an Excel file").into());
+    }
+    if is_gzip(&target_path.to_string_lossy()) {
+        return Err(AppError::validation("Can't write CSV content over a compressed archive").into());
+    }
+
+    let lock = file_lock(&target_path);
+    let _guard = lock.lock().await;
+
+    // Keep the existing file's column order so rewrites diff cleanly in git
+    let csv_content = match_existing_layout(&csv_content, &target_path)?;
+
+    // Re-saving identical content shouldn't spawn a backup or touch the file
+    if !content_differs(&target_path, csv_content.as_bytes()) {
+        log::debug!("CSV file unchanged: {}", target_path.display());
+        return Ok(WriteResult {
+            path: target_path.to_string_lossy().to_string(),
+            changed: false,
+        });
+    }
+
+    // Create backup before overwriting if file exists
+    if target_path.exists() {
+        create_backup_file(target_path.to_str().unwrap()).await?;
+    }
+    
+    // Write new content to the file
+    write_atomic(&target_path, csv_content.as_bytes())
+        .with_context(|| format!("Failed to write CSV content to file: {}", target_path.display()))?;
+    refresh_last_good(&target_path, csv_content.as_bytes());
+    
+    log::info!("CSV file saved to: {}", target_path.display());
+    Ok(WriteResult {
+        path: target_path.to_string_lossy().to_string(),
+        changed: true,
+    })
+}
+
+// Where write_csv_content should put a file: inside `directory` when given,
+// otherwise the path itself, with bare filenames going to the Downloads folder
+fn resolve_write_target(file_path: &str, directory: Option<&str>) -> AnyhowResult<PathBuf> {
+    if let Some(directory) = directory.filter(|d| !d.trim().is_empty()) {
+        let directory = Path::new(directory);
+        if !directory.is_dir() {
+            return Err(AppError::file_not_found(format!("Directory not found: {}", directory.display())).into());
+        }
+        return Ok(directory.join(file_path));
+    }
+
+    let path = Path::new(file_path);
+
+    // If it's just a filename (no directory separators), save to Downloads folder
+    if path.parent().is_none() || path.parent() == Some(Path::new("")) {
+        // The platform Downloads folder honors XDG user dirs and Windows folder redirection.
+        // Without a home directory there is none; falling back to temp would lose the file
+        let downloads_dir = dirs::download_dir().ok_or_else(|| {
+            AppError::file_not_found(format!(
+                "Couldn't find a Downloads folder to save {} in. Choose a folder to save it to.",
+                file_path
+            ))
+        })?;
+        Ok(downloads_dir.join(file_path))
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+// Network shares have left zero-byte and truncated copies behind; read the backup
+// back and compare it with the source rather than trusting fs::copy
+fn verify_backup(source: &Path, backup: &Path) -> AnyhowResult<()> {
+    let original = fs::read(source).with_context(|| format!("Failed to read {:?} to verify the backup", source))?;
+    let copy = fs::read(backup).with_context(|| format!("Failed to read back backup {:?}", backup))?;
+    if original != copy {
+        return Err(AppError::io(format!(
+            "Backup {} is incomplete ({} of {} bytes); the original was not changed",
+            backup.display(),
+            copy.len(),
+            original.len()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+async fn create_backup_file(file_path: &str) -> AnyhowResult<String> {
+    let path = Path::new(file_path);
+    let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT);
+    
+    let backup_path = if path.parent().is_some() {
+        let (stem, extension) = backup_name_parts(path);
+        let backup_dir = backup_dir_for(path);
+        fs::create_dir_all(&backup_dir)
+            .with_context(|| format!("Failed to create backup directory {:?}", backup_dir))?;
+        backup_dir.join(format!("{}_backup_{}.{}", stem, timestamp, extension))
+    } else {
+        Path::new(&format!("backup_{}.csv", timestamp)).to_path_buf()
+    };
+
+    fs::copy(file_path, &backup_path)
+        .with_context(|| format!("Failed to create backup at {:?}", backup_path))?;
+    // Callers overwrite the original next, so a bad copy must stop them here
+    if let Err(e) = verify_backup(path, &backup_path) {
+        let _ = fs::remove_file(&backup_path);
+        return Err(e);
+    }
+
+    // The new backup is safely written, so failing to prune shouldn't fail the save
+    let max_backups = config().max_backups;
+    if max_backups > 0 {
+        if let Err(e) = prune_backups(path, max_backups) {
+            log::warn!("Failed to prune old backups: {}", e);
+        }
+    }
+
+    Ok(backup_path.to_string_lossy().to_string())
+}
+
+// Newest first, which is the order an operator picks from
+fn list_backup_files(file_path: &str) -> AnyhowResult<Vec<BackupInfo>> {
+    let backups = find_backups(Path::new(file_path))?;
+
+    Ok(backups
+        .into_iter()
+        .rev()
+        .map(|(timestamp, path)| BackupInfo {
+            path: path.to_string_lossy().to_string(),
+            timestamp,
+        })
+        .collect())
+}
+
+fn backup_usage(file_path: &str) -> AnyhowResult<BackupUsage> {
+    let mut backups = Vec::new();
+    for (timestamp, path) in find_backups(Path::new(file_path))?.into_iter().rev() {
+        let bytes = fs::metadata(&path)
+            .with_context(|| format!("Failed to read size of backup {:?}", path))?
+            .len();
+        backups.push(BackupSize {
+            path: path.to_string_lossy().to_string(),
+            timestamp,
+            bytes,
+        });
+    }
+
+    Ok(BackupUsage {
+        total_bytes: backups.iter().map(|b| b.bytes).sum(),
+        backups,
+    })
+}
+
+async fn restore_backup_file(backup_path: &str, target_path: &str) -> AnyhowResult<()> {
+    // Read first: backing up the current state may rotate this very backup away
+    let content = fs::read(backup_path)
+        .with_context(|| format!("Failed to read backup: {}", backup_path))?;
+
+    let lock = file_lock(Path::new(target_path));
+    let _guard = lock.lock().await;
+    if Path::new(target_path).exists() {
+        create_backup_file(target_path).await?;
+    }
+
+    write_atomic(Path::new(target_path), &content)
+        .with_context(|| format!("Failed to restore backup to {}", target_path))?;
+    refresh_last_good(Path::new(target_path), &content);
+
+    Ok(())
+}
+
+// Single rolling copy of the last write known to have landed intact, for when the
+// live file is damaged and no timestamped backup is usable
+fn last_good_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!("{}.lastgood", file_name))
+}
+
+// Read the file back after a write and only then take the copy, so a torn write
+// never replaces the known-good one. Every roster write calls this: saves,
+// write_csv_content, write_csv_data (undo, delete, add, imports and the other
+// bulk edits) and restore_backup. The write itself already succeeded, so
+// problems here are only logged
+fn refresh_last_good(path: &Path, written: &[u8]) {
+    match fs::read(path) {
+        Ok(on_disk) if on_disk == written => {
+            if let Err(e) = write_atomic(&last_good_path(path), written) {
+                log::warn!("Failed to update last good copy of {}: {}", path.display(), e);
+            }
+        }
+        Ok(_) => log::warn!(
+            "{} doesn't match what was just written; keeping the previous last good copy",
+            path.display()
+        ),
+        Err(e) => log::warn!("Failed to read back {}: {}", path.display(), e),
+    }
+}
+
+async fn restore_last_good_file(file_path: &str) -> AnyhowResult<()> {
+    let path = Path::new(file_path);
+    let last_good = last_good_path(path);
+    let content = match fs::read(&last_good) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::not_found(format!("No last good copy of {} exists", file_path)).into());
+        }
+        Err(e) => {
+            return Err(anyhow::Error::new(e).context(format!("Failed to read {}", last_good.display())));
+        }
+    };
+
+    let lock = file_lock(path);
+    let _guard = lock.lock().await;
+
+    // Keep whatever is there now, however broken, in case it still holds something
+    if path.exists() {
+        create_backup_file(file_path).await?;
+    }
+
+    write_atomic(path, &content).with_context(|| format!("Failed to restore last good copy to {}", file_path))?;
+    log::info!("Restored {} from its last good copy", file_path);
+    Ok(())
+}
+
+const ARCHIVE_FOLDER: &str = "archive";
+
+// Copies, not moves: the live roster and its backups stay where the app expects
+// them. Archiving again the same day refreshes that day's folder
+async fn archive_roster_files(file_path: &str, date: chrono::NaiveDate) -> AnyhowResult<String> {
+    let path = Path::new(file_path);
+    if !path.is_file() {
+        return Err(AppError::file_not_found(format!("File not found: {}", file_path)).into());
+    }
+    let archive_dir = path
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(ARCHIVE_FOLDER)
+        .join(date.format("%Y-%m-%d").to_string());
+    fs::create_dir_all(&archive_dir)
+        .with_context(|| format!("Failed to create archive folder {:?}", archive_dir))?;
+
+    // Hold the roster still so the archived copy matches what the backups lead up to
+    let lock = file_lock(path);
+    let _guard = lock.lock().await;
+
+    let backups = find_backups(path)?.into_iter().map(|(_, backup)| backup);
+    for source in std::iter::once(path.to_path_buf()).chain(backups) {
+        let Some(name) = source.file_name() else { continue };
+        let target = archive_dir.join(name);
+        fs::copy(&source, &target).with_context(|| format!("Failed to archive {:?}", source))?;
+        verify_backup(&source, &target)?;
+    }
+
+    log::info!("Archived {} to {}", file_path, archive_dir.display());
+    Ok(archive_dir.to_string_lossy().to_string())
+}
+
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+fn backup_name_parts(path: &Path) -> (&str, &str) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("backup");
+    let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("csv");
+    (stem, extension)
+}
+
+// Folder holding the backups of this file, honoring the backup_dir setting
+fn backup_dir_for(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    match config().backup_dir {
+        Some(dir) => parent.join(dir),
+        None => parent.to_path_buf(),
+    }
+}
+
+// Backups of a file, oldest first. Only names exactly matching
+// {stem}_backup_{timestamp}.{ext} count, so unrelated files are never picked up
+fn find_backups(path: &Path) -> AnyhowResult<Vec<(chrono::NaiveDateTime, PathBuf)>> {
+    let (stem, extension) = backup_name_parts(path);
+    let prefix = format!("{}_backup_", stem);
+    let suffix = format!(".{}", extension);
+
+    let backup_dir = backup_dir_for(path);
+    if !backup_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&backup_dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(timestamp) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix(&prefix))
+            .and_then(|n| n.strip_suffix(&suffix))
+        else {
+            continue;
+        };
+        if let Ok(parsed) = chrono::NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT) {
+            backups.push((parsed, entry.path()));
+        }
+    }
+
+    backups.sort();
+    Ok(backups)
+}
+
+// Delete the oldest backups of a file until at most `keep` remain
+fn prune_backups(path: &Path, keep: usize) -> AnyhowResult<()> {
+    let backups = find_backups(path)?;
+    let surplus = backups.len().saturating_sub(keep);
+
+    for (_, backup) in backups.into_iter().take(surplus) {
+        fs::remove_file(&backup)
+            .with_context(|| format!("Failed to remove old backup {:?}", backup))?;
+    }
+
+    Ok(())
+}
+
+// Helper function to get the parent directory where sister folders are located
+fn get_parent_dir() -> Result<std::path::PathBuf, AppError> {
+    if cfg!(target_os = "linux") {
+        // On Linux with AppImage, use APPIMAGE env var
+        std::env::var("APPIMAGE")
+            .ok()
+            .and_then(|appimage_path| {
+                Path::new(&appimage_path)
+                    .parent()
+                    .map(|p| p.to_path_buf())
+            })
+            .ok_or_else(|| AppError::io("Could not determine parent directory from APPIMAGE"))
+    } else if cfg!(target_os = "windows") {
+        // On Windows the installed .exe sits directly in the parent dir
+        std::env::current_exe()?
+            .parent()
+            .map(|p| p.to_path_buf())
+            .ok_or_else(|| AppError::io("Could not determine parent directory"))
+    } else {
+        // On macOS, the .app bundle is in parent dir
+        std::env::current_exe()?
+            .parent() // Contents/MacOS
+            .and_then(|p| p.parent()) // Contents
+            .and_then(|p| p.parent()) // .app bundle
+            .and_then(|p| p.parent()) // parent dir
+            .map(|p| p.to_path_buf())
+            .ok_or_else(|| AppError::io("Could not determine parent directory"))
+    }
+}
+
+// This app's own repo checkout (where run_update finds its script), a sibling folder of the installed app
+fn get_app_repo_dir() -> Result<PathBuf, AppError> {
+    Ok(get_parent_dir()?.join(config().app_repo_folder))
+}
+
+// Photos are filed by barcode in a folder beside the app: photos/<barcode>
+const PHOTOS_FOLDER: &str = "photos";
+
+fn player_photos_dir(parent_dir: &Path, barcode: &str) -> Result<PathBuf, AppError> {
+    let barcode = barcode.trim();
+    if barcode.is_empty() {
+        return Err(AppError::validation("A barcode is required to find the player's photos"));
+    }
+    if barcode.contains(['/', '\\']) || barcode == "." || barcode == ".." {
+        return Err(AppError::validation(format!("\"{}\" can't be used as a photo folder name", barcode)));
+    }
+    Ok(parent_dir.join(PHOTOS_FOLDER).join(barcode))
+}
+
+fn get_barcodes_dir() -> Result<PathBuf, AppError> {
+    Ok(get_parent_dir()?.join(config().git_repo_folder))
+}
+
+// Checked up front by the git commands, since a missing git otherwise surfaces
+// as a bare OS spawn error that front-desk staff can't act on
+fn ensure_git_installed() -> Result<(), AppError> {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Ok(()),
+        result => Err(AppError::Git {
+            message: "Git is not installed or not on PATH.".to_string(),
+            suggestion: Some(
+                "Install Git from https://git-scm.com/downloads (on macOS, run \"xcode-select --install\"), then restart the app."
+                    .to_string(),
+            ),
+            raw: result.err().map(|e| e.to_string()),
+        }),
+    }
+}
+
+fn barcodes_dir_missing_error() -> AppError {
+    AppError::file_not_found(format!(
+        "{} folder not found. Please pull first.",
+        config().git_repo_folder
+    ))
+}
+
+// Map the common ways `git pull` fails to something front-desk staff can act on.
+// Git reports merge conflicts on stdout, so both streams are checked.
+fn explain_pull_failure(stdout: &str, stderr: &str, rebase: bool) -> AppError {
+    let raw = format!("{}{}", stderr, stdout).trim().to_string();
+
+    let (message, suggestion) = if raw.contains("would be overwritten by merge")
+        || raw.contains("cannot pull with rebase")
+    {
+        (
+            "You have unsaved changes to files the update also changes.",
+            "Push them first or discard them, then pull again.",
+        )
+    } else if rebase && (raw.contains("CONFLICT") || raw.contains("could not apply")) {
+        (
+            "The update conflicts with your local commits, and the pull stopped partway through a rebase.",
+            "Ask for help finishing the rebase, or run \"git rebase --abort\" in the barcodes folder to undo it.",
+        )
+    } else if raw.contains("CONFLICT") {
+        (
+            "The update conflicts with your local changes to the same files.",
+            "Ask for help resolving the conflict before making more changes.",
+        )
+    } else if raw.contains("diverged") || raw.contains("divergent branches") {
+        (
+            "Your copy and the shared copy have both changed since the last pull.",
+            "Push your changes first, then pull again.",
+        )
+    } else {
+        return AppError::Git {
+            message: "Git pull failed.".to_string(),
+            suggestion: None,
+            raw: Some(raw),
+        };
+    };
+
+    AppError::Git {
+        message: message.to_string(),
+        suggestion: Some(suggestion.to_string()),
+        raw: Some(raw),
+    }
+}
+
+// `rebase` replays local commits on top of the remote instead of merging;
+// plain pull stays the default
+#[tauri::command]
+fn git_pull(rebase: Option<bool>) -> Result<String, AppError> {
+    ensure_writable()?;
+    ensure_git_installed()?;
+    let rebase = rebase.unwrap_or(false);
+    let parent_dir = get_parent_dir()?;
+    let settings = config();
+    let barcodes_dir = parent_dir.join(&settings.git_repo_folder);
+
+    if !barcodes_dir.exists() {
+        // Clone the repository
+        log::info!("Cloning {} repository...", settings.git_repo_folder);
+        let output = Command::new("git")
+            .args(["clone", &settings.git_repo_url, &settings.git_repo_folder])
+            .current_dir(&parent_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git clone: {}", e)))?;
+
+        if output.status.success() {
+            Ok("Repository cloned successfully!".to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log::error!("Git clone failed: {}", stderr.trim());
+            Err(AppError::git(format!("Git clone failed: {}", stderr)))
+        }
+    } else {
+        // Pull latest changes
+        log::info!("Pulling latest changes in {}...", settings.git_repo_folder);
+        let args: &[&str] = if rebase { &["pull", "--rebase"] } else { &["pull"] };
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git pull: {}", e)))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if output.status.success() {
+            log::info!("Pull successful: {}", stdout.trim());
+            Ok(format!("Pull successful: {}", stdout.trim()))
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log::error!("Git pull failed: {}{}", stderr.trim(), stdout.trim());
+            Err(explain_pull_failure(&stdout, &stderr, rebase))
+        }
+    }
+}
+
+// Whether the configured repo URL is reachable with this machine's credentials,
+// so the UI can ask for SSH key setup before a clone fails halfway. Runs
+// non-interactively: a missing key must fail here rather than wait for a prompt
+#[tauri::command]
+fn check_git_access() -> Result<bool, AppError> {
+    ensure_git_installed()?;
+    let url = config().git_repo_url;
+
+    let output = Command::new("git")
+        .args(["ls-remote", "--heads", &url])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git ls-remote: {}", e)))?;
+
+    if output.status.success() {
+        return Ok(true);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if is_auth_failure(&stderr) {
+        log::warn!("No git access to {}: {}", url, stderr);
+        return Ok(false);
+    }
+    // Anything else (offline, bad URL) says nothing about the key
+    Err(AppError::Git {
+        message: format!("Couldn't reach {}.", url),
+        suggestion: Some("Check the internet connection and the repository URL in settings.".to_string()),
+        raw: Some(stderr),
+    })
+}
+
+fn is_auth_failure(stderr: &str) -> bool {
+    [
+        "Permission denied",
+        "Host key verification failed",
+        "Authentication failed",
+        "could not read Username",
+        "Repository not found",
+    ]
+    .iter()
+    .any(|marker| stderr.contains(marker))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PushResult {
+    pub commit_hash: String,
+    pub summary: String,
+}
+
+// `paths` (relative to the barcodes folder) limits the commit to those files;
+// without them everything in the folder is added, as before
+#[tauri::command]
+fn git_push(commit_message: String, paths: Option<Vec<String>>) -> Result<PushResult, AppError> {
+    ensure_writable()?;
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+    let pathspec = push_pathspec(paths.as_deref().unwrap_or_default())?;
+
+    // Git add
+    log::info!("Adding changes...");
+    let add_output = Command::new("git")
+        .arg("add")
+        .args(&pathspec)
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git add: {}", e)))?;
+
+    if !add_output.status.success() {
+        let stderr = String::from_utf8_lossy(&add_output.stderr);
+        return Err(AppError::git(format!("Git add failed: {}", stderr)));
+    }
+
+    // Git commit, attributed to the configured operator without touching global git config
+    log::info!("Committing changes...");
+    let settings = config();
+    let mut identity_args = Vec::new();
+    if let Some(name) = &settings.git_user_name {
+        identity_args.extend(["-c".to_string(), format!("user.name={}", name)]);
+    }
+    if let Some(email) = &settings.git_user_email {
+        identity_args.extend(["-c".to_string(), format!("user.email={}", email)]);
+    }
+    let commit_output = Command::new("git")
+        .args(&identity_args)
+        .args(["commit", "-m", &commit_message])
+        // Commit only the chosen files, even if something else was already staged
+        .args(if paths.is_some() { pathspec.as_slice() } else { &[] })
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git commit: {}", e)))?;
+
+    if !commit_output.status.success() {
+        let stderr = String::from_utf8_lossy(&commit_output.stderr);
+        let stdout = String::from_utf8_lossy(&commit_output.stdout);
+        // Check if it's just "nothing to commit"
+        if stdout.contains("nothing to commit") || stderr.contains("nothing to commit") {
+            return Err(AppError::git("Nothing to commit - no changes detected."));
+        }
+        return Err(AppError::git(format!("Git commit failed: {}", stderr)));
+    }
+
+    // Record what we're about to push so the operator can confirm it later
+    let hash_output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git rev-parse: {}", e)))?;
+    let commit_hash = String::from_utf8_lossy(&hash_output.stdout).trim().to_string();
+    let summary = commit_message.lines().next().unwrap_or("").trim().to_string();
+
+    // Git push
+    log::info!("Pushing changes...");
+    let push = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git push: {}", e)))
+    };
+    let mut push_output = push(&["push"])?;
+
+    // A branch made locally (e.g. by switch_branch) has nothing to push to yet;
+    // publish it under the same name, as `git push -u` would suggest
+    if !push_output.status.success() && is_missing_upstream(&String::from_utf8_lossy(&push_output.stderr)) {
+        let branch_output = Command::new("git")
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git rev-parse: {}", e)))?;
+        let branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
+        if !branch_output.status.success() || branch.is_empty() || branch == "HEAD" {
+            return Err(AppError::Git {
+                message: "Your changes were committed, but this branch isn't set up to push anywhere.".to_string(),
+                suggestion: Some("Switch to a named branch, then push again.".to_string()),
+                raw: Some(String::from_utf8_lossy(&push_output.stderr).trim().to_string()),
+            });
+        }
+
+        log::info!("No upstream for {}, pushing with -u origin", branch);
+        push_output = push(&["push", "-u", "origin", &branch])?;
+    }
+
+    if push_output.status.success() {
+        log::info!("Pushed {}: {}", commit_hash, summary);
+        Ok(PushResult { commit_hash, summary })
+    } else {
+        let stderr = String::from_utf8_lossy(&push_output.stderr);
+        log::error!("Git push failed: {}", stderr.trim());
+        Err(AppError::git(format!("Git push failed: {}", stderr)))
+    }
+}
+
+fn is_missing_upstream(stderr: &str) -> bool {
+    stderr.contains("has no upstream branch")
+}
+
+// "." for the whole folder, otherwise the chosen paths after "--" so a name
+// starting with a dash can't be read as an option
+fn push_pathspec(paths: &[String]) -> Result<Vec<String>, AppError> {
+    if paths.is_empty() {
+        return Ok(vec![".".to_string()]);
+    }
+    if paths.iter().any(|path| path.trim().is_empty()) {
+        return Err(AppError::validation("File paths to push can't be blank"));
+    }
+    Ok(std::iter::once("--".to_string()).chain(paths.iter().cloned()).collect())
+}
+
+#[tauri::command]
+fn get_barcodes_path() -> Result<String, AppError> {
+    let barcodes_dir = get_barcodes_dir()?;
+    Ok(barcodes_dir.to_string_lossy().to_string())
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub branch: String,
+    pub modified: usize,
+    pub added: usize,
+    pub deleted: usize,
+    pub untracked: usize,
+}
+
+#[tauri::command]
+fn git_status() -> Result<GitStatus, AppError> {
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+
+    let status_output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git status: {}", e)))?;
+
+    if !status_output.status.success() {
+        let stderr = String::from_utf8_lossy(&status_output.stderr);
+        return Err(AppError::git(format!("Git status failed: {}", stderr)));
+    }
+
+    let branch_output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git rev-parse: {}", e)))?;
+
+    if !branch_output.status.success() {
+        let stderr = String::from_utf8_lossy(&branch_output.stderr);
+        return Err(AppError::git(format!("Git rev-parse failed: {}", stderr)));
+    }
+
+    let mut status = parse_git_status(&String::from_utf8_lossy(&status_output.stdout));
+    status.branch = String::from_utf8_lossy(&branch_output.stdout).trim().to_string();
+    Ok(status)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    // None for binary files, and for untracked files git has no lines to compare
+    pub added: Option<usize>,
+    pub removed: Option<usize>,
+    pub staged: bool,
+    pub untracked: bool,
+}
+
+// Preview of what git_push would commit. Read-only: nothing is staged here,
+// but untracked files are listed since push's `git add .` will pick them up
+#[tauri::command]
+fn git_diff() -> Result<Vec<FileDiff>, AppError> {
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+
+    let git = |args: &[&str]| -> Result<String, AppError> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git {}: {}", args[0], e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::git(format!("Git {} failed: {}", args[0], stderr)));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    let mut diffs = parse_numstat(&git(&["diff", "--cached", "--numstat"])?, true);
+    diffs.extend(parse_numstat(&git(&["diff", "--numstat"])?, false));
+    diffs.extend(
+        git(&["ls-files", "--others", "--exclude-standard"])?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|path| FileDiff {
+                path: path.to_string(),
+                added: None,
+                removed: None,
+                staged: false,
+                untracked: true,
+            }),
+    );
+    Ok(diffs)
+}
+
+// `git diff --numstat` prints "<added>\t<removed>\t<path>", with "-" counts for binary files
+fn parse_numstat(output: &str, staged: bool) -> Vec<FileDiff> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let added = fields.next()?;
+            let removed = fields.next()?;
+            let path = fields.next()?;
+            Some(FileDiff {
+                path: path.to_string(),
+                added: added.parse().ok(),
+                removed: removed.parse().ok(),
+                staged,
+                untracked: false,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub branch: String,
+    // None when the branch has no upstream to compare against
+    pub ahead: Option<usize>,
+    pub behind: Option<usize>,
+    pub last_commit_at: Option<chrono::DateTime<chrono::FixedOffset>>,
+    // False when the fetch failed (e.g. offline) and the counts may be stale
+    pub fetched: bool,
+    pub summary: String,
+}
+
+// Read-only look at how the barcodes repo compares to the shared copy
+#[tauri::command]
+fn git_sync_state() -> Result<SyncState, AppError> {
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git {}: {}", args[0], e)))
+    };
+
+    let fetched = git(&["fetch", "--quiet"])?.status.success();
+
+    let branch_output = git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+    if !branch_output.status.success() {
+        let stderr = String::from_utf8_lossy(&branch_output.stderr);
+        return Err(AppError::git(format!("Git rev-parse failed: {}", stderr)));
+    }
+
+    // Fails when there's no upstream, which just means nothing to compare
+    let counts_output = git(&["rev-list", "--left-right", "--count", "HEAD...@{u}"])?;
+    let counts = if counts_output.status.success() {
+        parse_ahead_behind(&String::from_utf8_lossy(&counts_output.stdout))
+    } else {
+        None
+    };
+
+    let log_output = git(&["log", "-1", "--format=%cI"])?;
+    let last_commit_at =
+        chrono::DateTime::parse_from_rfc3339(String::from_utf8_lossy(&log_output.stdout).trim()).ok();
+
+    let mut state = SyncState {
+        branch: String::from_utf8_lossy(&branch_output.stdout).trim().to_string(),
+        ahead: counts.map(|(ahead, _)| ahead),
+        behind: counts.map(|(_, behind)| behind),
+        last_commit_at,
+        fetched,
+        summary: String::new(),
+    };
+    state.summary = describe_sync_state(&state);
+    Ok(state)
+}
+
+// `git rev-list --left-right --count HEAD...@{u}` prints "<ahead>\t<behind>"
+fn parse_ahead_behind(output: &str) -> Option<(usize, usize)> {
+    let mut counts = output.split_whitespace().map(|n| n.parse::<usize>().ok());
+    Some((counts.next()??, counts.next()??))
+}
+
+fn describe_sync_state(state: &SyncState) -> String {
+    let plural = |n: usize| if n == 1 { "commit" } else { "commits" };
+    let mut summary = match (state.ahead, state.behind) {
+        (None, _) | (_, None) => format!("{} has no remote branch to compare against", state.branch),
+        (Some(0), Some(0)) => "Up to date with remote".to_string(),
+        (Some(0), Some(behind)) => format!("{} {} behind remote — pull recommended", behind, plural(behind)),
+        (Some(ahead), Some(0)) => format!("{} {} ahead of remote — push to share", ahead, plural(ahead)),
+        (Some(ahead), Some(behind)) => format!(
+            "{} {} ahead and {} {} behind remote — pull, then push",
+            ahead,
+            plural(ahead),
+            behind,
+            plural(behind)
+        ),
+    };
+    if !state.fetched {
+        summary.push_str(" (couldn't reach remote; may be out of date)");
+    }
+    summary
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitBranches {
+    pub current: String,
+    pub local: Vec<String>,
+    // Remote-tracking names like "origin/staging"
+    pub remote: Vec<String>,
+}
+
+#[tauri::command]
+fn git_list_branches() -> Result<GitBranches, AppError> {
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+
+    let run = |args: &[&str]| -> Result<String, AppError> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&barcodes_dir)
+            .output()
+            .map_err(|e| AppError::git(format!("Failed to run git branch: {}", e)))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::git(format!("Git branch failed: {}", stderr)));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    };
+
+    Ok(GitBranches {
+        current: run(&["rev-parse", "--abbrev-ref", "HEAD"])?.trim().to_string(),
+        local: parse_branch_list(&run(&["branch", "--format=%(refname:short)"])?),
+        remote: parse_branch_list(&run(&["branch", "-r", "--format=%(refname:short)"])?)
+            .into_iter()
+            // The remote's HEAD pointer shows up as "origin" or "origin/HEAD"
+            .filter(|name| name.contains('/') && !name.ends_with("/HEAD"))
+            .collect(),
+    })
+}
+
+fn parse_branch_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Switch the barcodes repo to another branch; a branch that only exists on the
+// remote is checked out as a new tracking branch
+#[tauri::command]
+fn git_checkout(branch: String) -> Result<String, AppError> {
+    ensure_writable()?;
+    ensure_git_installed()?;
+    let branch = branch.trim();
+    // A leading dash would be read as an option rather than a branch name
+    if branch.is_empty() || branch.starts_with('-') {
+        return Err(AppError::validation(format!("Invalid branch name: \"{}\"", branch)));
+    }
+
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+
+    log::info!("Switching to branch {}...", branch);
+    let output = Command::new("git")
+        .args(["checkout", branch])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git checkout: {}", e)))?;
+
+    if output.status.success() {
+        Ok(format!("Switched to branch {}", branch))
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(explain_checkout_failure(&stderr, branch))
+    }
+}
+
+fn explain_checkout_failure(stderr: &str, branch: &str) -> AppError {
+    let raw = stderr.trim().to_string();
+
+    let (message, suggestion) = if raw.contains("would be overwritten by checkout")
+        || raw.contains("commit your changes or stash them")
+    {
+        (
+            format!("You have unsaved changes that would be lost by switching to {}.", branch),
+            "Push them first or discard them, then switch branches.",
+        )
+    } else if raw.contains("did not match any") || raw.contains("invalid reference") {
+        (
+            format!("There is no branch named {}.", branch),
+            "Pull first to pick up new branches, or check the spelling.",
+        )
+    } else {
+        return AppError::Git {
+            message: "Git checkout failed.".to_string(),
+            suggestion: None,
+            raw: Some(raw),
+        };
+    };
+
+    AppError::Git {
+        message,
+        suggestion: Some(suggestion.to_string()),
+        raw: Some(raw),
+    }
+}
+
+// Reset the barcodes repo to its last commit, deleting untracked files too.
+// Destructive, so it only ever runs in the configured barcodes folder
+#[tauri::command]
+fn git_discard() -> Result<String, AppError> {
+    ensure_writable()?;
+    ensure_git_installed()?;
+    let barcodes_dir = get_barcodes_dir()?;
+
+    if !barcodes_dir.exists() {
+        return Err(barcodes_dir_missing_error());
+    }
+    // Without its own .git, git would walk up and reset whatever repo contains the folder
+    if !barcodes_dir.join(".git").exists() {
+        return Err(AppError::git(format!(
+            "{} is not a git repository; refusing to discard anything",
+            barcodes_dir.display()
+        )));
+    }
+
+    let status_output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git status: {}", e)))?;
+    let status = parse_git_status(&String::from_utf8_lossy(&status_output.stdout));
+
+    log::info!("Discarding local changes...");
+    let reset_output = Command::new("git")
+        .args(["reset", "--hard"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git reset: {}", e)))?;
+
+    if !reset_output.status.success() {
+        let stderr = String::from_utf8_lossy(&reset_output.stderr);
+        return Err(AppError::git(format!("Git reset failed: {}", stderr)));
+    }
+
+    let clean_output = Command::new("git")
+        .args(["clean", "-fd"])
+        .current_dir(&barcodes_dir)
+        .output()
+        .map_err(|e| AppError::git(format!("Failed to run git clean: {}", e)))?;
+
+    if !clean_output.status.success() {
+        let stderr = String::from_utf8_lossy(&clean_output.stderr);
+        return Err(AppError::git(format!("Git clean failed: {}", stderr)));
+    }
+
+    Ok(describe_discarded(&status))
+}
+
+fn describe_discarded(status: &GitStatus) -> String {
+    let parts: Vec<String> = [
+        (status.modified, "modified"),
+        (status.added, "added"),
+        (status.deleted, "deleted"),
+        (status.untracked, "untracked"),
+    ]
+    .iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{} {}", count, label))
+    .collect();
+
+    if parts.is_empty() {
+        "Nothing to discard - already matches the last commit.".to_string()
+    } else {
+        format!("Discarded {} file(s).", parts.join(", "))
+    }
+}
+
+// Count files in `git status --porcelain` output; each file lands in exactly one bucket
+fn parse_git_status(porcelain: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+
+    for line in porcelain.lines() {
+        let code = line.get(..2).unwrap_or("");
+        if code.is_empty() {
+            continue;
+        }
+        if code == "??" {
+            status.untracked += 1;
+        } else if code.contains('D') {
+            status.deleted += 1;
+        } else if code.contains('A') {
+            status.added += 1;
+        } else {
+            status.modified += 1;
+        }
+    }
+
+    status
+}
+
+fn restore_window_geometry(window: &tauri::WebviewWindow, geometry: WindowGeometry) {
+    let _ = window.set_size(tauri::PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(tauri::PhysicalPosition::new(geometry.x, geometry.y));
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+// A maximized window keeps the size it had before maximizing, so un-maximizing
+// after the next launch lands somewhere sensible
+fn save_window_geometry(window: &tauri::Window) -> AnyhowResult<()> {
+    let maximized = window.is_maximized()?;
+    let geometry = match (maximized, config().window) {
+        (true, Some(previous)) => WindowGeometry { maximized, ..previous },
+        _ => {
+            let size = window.inner_size()?;
+            let position = window.outer_position()?;
+            WindowGeometry {
+                width: size.width,
+                height: size.height,
+                x: position.x,
+                y: position.y,
+                maximized,
+            }
+        }
+    };
+    update_config(|current| current.window = Some(geometry))
+}
+
+// Build the menu bar and attach it. Called again whenever the Recent list changes
+fn install_menu(app: &tauri::AppHandle) -> tauri::Result<()> {
+    // Create menu items. Accelerators fire the same menu event as a click,
+    // so on_menu_event handles shortcuts and clicks alike
+    let open_item = MenuItemBuilder::new("Open")
+        .id("open")
+        .accelerator("CmdOrCtrl+O")
+        .build(app)?;
+
+    let reveal_item = MenuItemBuilder::new("Show in Folder")
+        .id("reveal")
+        .accelerator("CmdOrCtrl+Shift+R")
+        .build(app)?;
+
+    let update_item = MenuItemBuilder::new("Update App")
+        .id("update")
+        .accelerator("CmdOrCtrl+U")
+        .build(app)?;
+
+    let pull_item = MenuItemBuilder::new("Pull")
+        .id("pull")
+        .accelerator("CmdOrCtrl+Shift+P")
+        .build(app)?;
+
+    let push_item = MenuItemBuilder::new("Push")
+        .id("push")
+        .accelerator("CmdOrCtrl+Shift+K")
+        .build(app)?;
+
+    let sync_item = MenuItemBuilder::new("Sync Status")
+        .id("sync-status")
+        .accelerator("CmdOrCtrl+Shift+S")
+        .build(app)?;
+
+    let checkout_item = MenuItemBuilder::new("Switch Branch...")
+        .id("checkout")
+        .accelerator("CmdOrCtrl+Shift+B")
+        .build(app)?;
+
+    // Recently opened rosters, newest first; ids carry the path for on_menu_event
+    let mut recent_menu = SubmenuBuilder::new(app, "Recent");
+    let recent_files = recent_files();
+    if recent_files.is_empty() {
+        let empty_item = MenuItemBuilder::new("No Recent Files")
+            .id("recent-none")
+            .enabled(false)
+            .build(app)?;
+        recent_menu = recent_menu.item(&empty_item);
+    }
+    for path in &recent_files {
+        let label = Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        let item = MenuItemBuilder::new(label)
+            .id(format!("{}{}", RECENT_MENU_PREFIX, path))
+            .build(app)?;
+        recent_menu = recent_menu.item(&item);
+    }
+    let recent_menu = recent_menu.build()?;
+
+    // Create File submenu
+    let file_menu = SubmenuBuilder::new(app, "File")
+        .item(&open_item)
+        .item(&recent_menu)
+        .item(&reveal_item)
+        .item(&update_item)
+        .build()?;
+
+    // Create Git submenu
+    let git_menu = SubmenuBuilder::new(app, "Git")
+        .item(&pull_item)
+        .item(&push_item)
+        .separator()
+        .item(&sync_item)
+        .item(&checkout_item)
+        .build()?;
+
+    // Build the menu bar
+    let menu = MenuBuilder::new(app)
+        .item(&file_menu)
+        .item(&git_menu)
+        .build()?;
+
+    // Set the menu - on macOS it must be set on the app, on Linux on the window
+    #[cfg(target_os = "macos")]
+    app.set_menu(menu)?;
+
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = app.get_webview_window("main") {
+        window.set_menu(menu)?;
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
+        .plugin(
+            tauri_plugin_log::Builder::new()
+                .clear_targets()
+                .target(tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout))
+                .target(tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
+                    file_name: Some(LOG_FILE_NAME.to_string()),
+                }))
+                // Roll the log over at 1 MB and keep the last few, so it can't fill the disk
+                .max_file_size(1_000_000)
+                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(LOG_FILES_KEPT))
+                // Let everything through here; the configured level is applied in setup
+                .level(log::LevelFilter::Trace)
+                .build(),
+        )
         .setup(|app| {
-            // Create menu items
-            let open_item = MenuItemBuilder::new("Open")
-                .id("open")
-                .build(app)?;
-
-            let update_item = MenuItemBuilder::new("Update App")
-                .id("update")
-                .build(app)?;
-
-            let pull_item = MenuItemBuilder::new("Pull")
-                .id("pull")
-                .build(app)?;
-
-            let push_item = MenuItemBuilder::new("Push")
-                .id("push")
-                .build(app)?;
-
-            // Create File submenu
-            let file_menu = SubmenuBuilder::new(app, "File")
-                .item(&open_item)
-                .item(&update_item)
-                .build()?;
-
-            // Create Git submenu
-            let git_menu = SubmenuBuilder::new(app, "Git")
-                .item(&pull_item)
-                .item(&push_item)
-                .build()?;
-
-            // Build the menu bar
-            let menu = MenuBuilder::new(app)
-                .item(&file_menu)
-                .item(&git_menu)
-                .build()?;
-
-            // Set the menu - on macOS it must be set on the app, on Linux on the window
-            #[cfg(target_os = "macos")]
-            app.set_menu(menu)?;
-
-            #[cfg(not(target_os = "macos"))]
-            if let Some(window) = app.get_webview_window("main") {
-                window.set_menu(menu)?;
+            // Start the session clock at launch rather than at the first edit
+            app.manage(Mutex::new(SessionStats::new()));
+
+            // Load persisted settings before any command needs them
+            if let Ok(config_dir) = app.path().app_config_dir() {
+                init_config(&config_dir);
+            }
+            log::set_max_level(config().log_level.parse().unwrap_or(log::LevelFilter::Info));
+
+            install_menu(app.handle())?;
+            if let (Some(geometry), Some(window)) = (config().window, app.get_webview_window("main")) {
+                restore_window_geometry(&window, geometry);
+            }
+
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                if let Err(e) = save_window_geometry(window) {
+                    log::warn!("Failed to save window size: {}", e);
+                }
+            }
+        })
+        .on_menu_event(|app, event| {
+            let id = event.id().as_ref();
+            log::debug!("Menu item clicked: {}", id);
+
+            match id {
+                "open" => {
+                    let _ = app.emit("menu-open-file", ());
+                }
+                "reveal" => {
+                    let _ = app.emit("menu-reveal-in-folder", ());
+                }
+                "update" => {
+                    let _ = app.emit("menu-update-app", ());
+                }
+                "pull" => {
+                    let _ = app.emit("menu-git-pull", ());
+                }
+                "push" => {
+                    let _ = app.emit("menu-git-push", ());
+                }
+                "sync-status" => {
+                    let _ = app.emit("menu-git-sync-status", ());
+                }
+                "checkout" => {
+                    let _ = app.emit("menu-git-checkout", ());
+                }
+                _ => {
+                    if let Some(path) = id.strip_prefix(RECENT_MENU_PREFIX) {
+                        let _ = app.emit("menu-open-recent", path.to_string());
+                    }
+                }
+            }
+        })
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            load_csv,
+            add_recent_file,
+            save_player,
+            preview_save,
+            save_draft,
+            get_draft,
+            clear_draft,
+            parse_products,
+            get_edit_log,
+            get_columns,
+            get_player,
+            decode_barcode,
+            search_players,
+            players_for_team,
+            players_alphabetical,
+            validate_roster,
+            validate_against_schema,
+            validate_field,
+            roster_summary,
+            teams_with_counts,
+            incomplete_orders,
+            find_shared_contacts,
+            undo_last_edit,
+            delete_player,
+            add_player,
+            duplicate_player,
+            create_backup,
+            archive_roster,
+            export_team,
+            export_labels,
+            export_json,
+            import_json,
+            merge_rosters,
+            remap_barcodes,
+            normalize_teams,
+            detect_encoding_issues,
+            fix_encoding,
+            list_backups,
+            backup_disk_usage,
+            diff_backups,
+            restore_backup,
+            restore_last_good,
+            set_backup_dir,
+            set_max_backups,
+            get_config,
+            set_config,
+            set_read_only,
+            is_read_only,
+            set_git_repo,
+            set_app_repo_folder,
+            set_git_identity,
+            get_log_path,
+            reveal_in_folder,
+            open_player_photos,
+            set_log_level,
+            write_csv_file,
+            app_version,
+            session_stats,
+            check_for_update,
+            run_update,
+            run_update_captured,
+            git_pull,
+            git_push,
+            git_status,
+            git_sync_state,
+            check_git_access,
+            git_diff,
+            git_discard,
+            git_list_branches,
+            git_checkout,
+            get_barcodes_path
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bom_prefixed_csv() {
+        let content = include_str!("../tests/fixtures/bom_roster.csv");
+        assert!(content.starts_with('\u{feff}'));
+
+        let data = parse_csv_content(content, "bom_roster.csv", &mut |_, _| {}).unwrap();
+
+        assert_eq!(data.players.len(), 2);
+        assert_eq!(data.players[0].barcode, "1001");
+        assert_eq!(data.players[0].first_name, "Ava");
+    }
+
+    #[test]
+    fn ignores_trailing_blank_rows() {
+        let content = include_str!("../tests/fixtures/trailing_blank_roster.csv");
+        assert!(content.ends_with(",,,,,,,,,\n\n"));
+
+        let data = parse_csv_content(content, "trailing_blank_roster.csv", &mut |_, _| {}).unwrap();
+
+        assert_eq!(data.players.len(), 2);
+        assert!(data.skipped_rows.is_empty());
+        assert_eq!(data.teams, vec!["U10 Red"]);
+    }
+
+    #[test]
+    fn skips_malformed_rows_and_keeps_the_rest() {
+        let content = "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,7,N,,,,\n\
+            1002,U10 Red,Ben\n\
+            1003,U12 Blue,Cal,Diaz,3,N,,,,\n";
+
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        assert_eq!(data.players.len(), 2);
+        assert_eq!(data.skipped_rows.len(), 1);
+        assert_eq!(data.skipped_rows[0].line, 3);
+    }
+
+    #[test]
+    fn reports_missing_required_headers() {
+        let content = "First Name,Last Name,Scan Code\nAva,Smith,1001\n";
+
+        let err = parse_csv_content(content, "barcodes.csv", &mut |_, _| {}).unwrap_err();
+        let missing = err.downcast_ref::<MissingHeadersError>().unwrap();
+
+        assert_eq!(missing.missing, vec!["Barcode Number", "Team"]);
+    }
+
+    #[test]
+    fn rewrite_preserves_column_order_and_extra_columns() {
+        let content = "Team,Barcode Number,Age,Last Name,First Name,Parent Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+            U10 Red,1001,9,Smith,Ava,Jo Smith,7,N,,,,\n\
+            U10 Red,1002,10,Jones,Ben,Kim Jones,12,N,,,,\n";
+
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+        assert!(data.skipped_rows.is_empty());
+        assert_eq!(data.players[0].other_fields["Age"], "9");
+
+        let rendered = String::from_utf8(render_csv_data(&data).unwrap()).unwrap();
+        assert_eq!(rendered, content);
+    }
+
+    #[test]
+    fn matches_column_order_of_existing_file() {
+        let path = write_fixture(
+            "column_order.csv",
+            "Team,Barcode Number,First Name,Last Name\nU10 Red,1001,Ava,Smith\n",
+        );
+
+        let incoming = "Barcode Number,Team,First Name,Last Name,Notes\n1001,U10 Red,Ava,Smith,late\n";
+        let reordered = match_existing_layout(incoming, Path::new(&path)).unwrap();
+
+        assert_eq!(
+            reordered,
+            "Team,Barcode Number,First Name,Last Name,Notes\nU10 Red,1001,Ava,Smith,late\n"
+        );
+    }
+
+    #[test]
+    fn preserves_crlf_line_endings() {
+        let crlf = "Barcode Number,Team,First Name,Last Name\r\n1001,U10 Red,Ava,Smith\r\n1002,U10 Red,Bo,\"Lee\nJr\"\r\n";
+        let data = parse_csv_content(crlf, "roster.csv", &mut |_, _| {}).unwrap();
+        assert_eq!(data.line_ending, LineEnding::Crlf);
+        assert_eq!(render_csv_data(&data).unwrap(), crlf.as_bytes());
+
+        // Frontend-rendered content is LF; a CRLF file on disk keeps its endings
+        let path = write_fixture("crlf_layout.csv", crlf);
+        let incoming = "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n";
+        assert_eq!(
+            match_existing_layout(incoming, Path::new(&path)).unwrap(),
+            "Barcode Number,Team,First Name,Last Name\r\n1001,U10 Red,Ava,Smith\r\n"
+        );
+    }
+
+    #[test]
+    fn dialect_file_overrides_detection() {
+        // Own folder, since the dialect file applies to every roster beside it
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests").join("dialect");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(DIALECT_FILE_NAME),
+            r#"{ "delimiter": ";", "quote": "'", "has_headers": false, "columns": ["Barcode Number", "Team", "First Name", "Last Name"] }"#,
+        )
+        .unwrap();
+        let content = "1001;U10 Red;Ava;'Smith; Jr'\n1002;U10 Red;Bo;Lee\n";
+        let path = dir.join("league.csv");
+        fs::write(&path, content).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let data = read_csv_file(&path, &mut |_, _| {}).unwrap();
+        assert_eq!(data.players.len(), 2);
+        assert_eq!(data.players[0].last_name, "Smith; Jr");
+        assert_eq!(data.headers, ["Barcode Number", "Team", "First Name", "Last Name"]);
+        assert_eq!(render_csv_data(&data).unwrap(), content.as_bytes());
+
+        fs::write(dir.join(DIALECT_FILE_NAME), "{ not json").unwrap();
+        assert!(matches!(
+            AppError::from(read_csv_file(&path, &mut |_, _| {}).unwrap_err()),
+            AppError::Parse { .. }
+        ));
+    }
+
+    #[test]
+    fn reads_gzip_compressed_rosters() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(b"Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n")
+            .unwrap();
+        let path = std::env::temp_dir().join("mvs-photo-form-tests").join("2023_roster.csv.gz");
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let data = read_csv_file(&path, &mut |_, _| {}).unwrap();
+        assert_eq!(data.players[0].first_name, "Ava");
+
+        let err = tauri::async_runtime::block_on(load_csv_for_rewrite(&path)).unwrap_err();
+        assert!(matches!(AppError::from(err), AppError::Validation { .. }));
+    }
+
+    #[test]
+    fn parses_first_sheet_of_a_workbook() {
+        use calamine::{Data, Range};
+
+        let mut range = Range::new((0, 0), (3, 4));
+        let rows = [
+            vec![
+                Data::String("Barcode Number".into()),
+                Data::String("Team".into()),
+                Data::String("First Name".into()),
+                Data::String("Last Name".into()),
+                Data::String("Jersey Number".into()),
+            ],
+            vec![
+                Data::Float(1001.0),
+                Data::String("U10 Red".into()),
+                Data::String("Ava".into()),
+                Data::String("Smith, Jr".into()),
+                Data::Int(7),
+            ],
+            vec![
+                Data::Float(1002.0),
+                Data::String("U10 Red".into()),
+                Data::String("Ben".into()),
+                Data::String("Jones".into()),
+                Data::Empty,
+            ],
+        ];
+        for (r, row) in rows.into_iter().enumerate() {
+            for (c, value) in row.into_iter().enumerate() {
+                range.set_value((r as u32, c as u32), value);
+            }
+        }
+
+        let data = parse_xlsx_range(&range, "roster.xlsx", &mut |_, _| {}).unwrap();
+
+        assert_eq!(data.players.len(), 2);
+        assert_eq!(data.players[0].barcode, "1001");
+        assert_eq!(data.players[0].last_name, "Smith, Jr");
+        assert_eq!(data.players[0].jersey_number, "7");
+        assert_eq!(data.players[1].jersey_number, "");
+        assert_eq!(data.teams, vec!["U10 Red"]);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_csv() {
+        let parse = |content: &str| parse_csv_content(content, "photo.jpg", &mut |_, _| {});
+
+        let binary = parse("\u{0}\u{10}JFIF\u{0}\u{1}\n");
+        assert!(format!("{:#}", binary.unwrap_err()).contains("doesn't appear to be a CSV"));
+
+        let prose = parse("Dear parents, thanks for coming\n");
+        assert!(prose.is_err());
+
+        let single_word = parse("hello\nworld\n");
+        assert!(format!("{:#}", single_word.unwrap_err()).contains("delimited header"));
+
+        let path = std::env::temp_dir().join("mvs-photo-form-tests").join("not_utf8.csv");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, [0xff, 0xd8, 0xff, 0xe0, b',', b'\n']).unwrap();
+        let err = tauri::async_runtime::block_on(load_csv_file(&path.to_string_lossy())).unwrap_err();
+        assert!(matches!(AppError::from(err), AppError::Parse { .. }));
+    }
+
+    #[test]
+    fn reports_progress_while_parsing() {
+        let mut content = String::from("Barcode Number,Team,First Name,Last Name\n");
+        for i in 0..600 {
+            content.push_str(&format!("{},U10 Red,Ava,Smith\n", 1000 + i));
+        }
+
+        let mut reports = Vec::new();
+        let data = parse_csv_content(&content, "roster.csv", &mut |rows, total| reports.push((rows, total))).unwrap();
+
+        assert_eq!(data.players.len(), 600);
+        assert_eq!(reports, vec![(250, 600), (500, 600)]);
+    }
+
+    #[test]
+    fn preserves_quote_everything_style() {
+        let quoted = "\"Barcode Number\",\"Team\",\"First Name\",\"Last Name\",\"Email\"\n\
+            \"1001\",\"U10 Red\",\"Ava\",\"Smith\",\"\"\n";
+        let data = parse_csv_content(quoted, "roster.csv", &mut |_, _| {}).unwrap();
+        assert_eq!(data.quote_style, QuoteStyle::Always);
+        assert_eq!(render_csv_data(&data).unwrap(), quoted.as_bytes());
+
+        let plain = "Barcode Number,Team,First Name,Last Name,Email\n1001,U10 Red,Ava,\"Smith, Jr\",\n";
+        let data = parse_csv_content(plain, "roster.csv", &mut |_, _| {}).unwrap();
+        assert_eq!(data.quote_style, QuoteStyle::Necessary);
+        assert_eq!(render_csv_data(&data).unwrap(), plain.as_bytes());
+    }
+
+    #[test]
+    fn reports_duplicate_barcodes() {
+        let content = "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,7,N,,,,\n\
+            1001,U10 Red,Amy,Smith,8,N,,,,\n\
+            1002,U10 Red,Ben,Jones,12,N,,,,\n\
+            1001,U10 Red,Al,Smith,9,N,,,,\n";
+
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        assert_eq!(data.players.len(), 4);
+        assert_eq!(data.duplicates, vec!["1001"]);
+    }
+
+    #[test]
+    fn validates_every_player_in_the_roster() {
+        let content = "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,7,N,5551234567,ava@example.com,,\n\
+            1002,,Ben,,12,N,555-1234,ben@,,\n\
+            1001,U10 Red,Amy,Smith,8,N,,,,\n";
+
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+        let issues = validate_players(&data);
+
+        let for_1002: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.barcode == "1002")
+            .map(|issue| issue.message.as_str())
+            .collect();
+        assert_eq!(for_1002.len(), 4);
+        assert!(for_1002.contains(&"Missing last name"));
+        assert!(for_1002.contains(&"Not assigned to a team"));
+        assert!(for_1002.iter().any(|m| m.starts_with("Invalid email")));
+        assert!(for_1002.iter().any(|m| m.starts_with("Invalid phone")));
+
+        let for_1001: Vec<&ValidationIssue> = issues.iter().filter(|issue| issue.barcode == "1001").collect();
+        assert_eq!(for_1001.len(), 1);
+        assert!(for_1001[0].message.contains("more than once"));
+    }
+
+    #[test]
+    fn load_roster_reads_without_an_async_runtime() {
+        let path = write_fixture(
+            "load_roster.csv",
+            "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n",
+        );
+        let data = load_roster(&path).unwrap();
+        assert_eq!(data.players[0].first_name, "Ava");
+        assert!(matches!(load_roster("missing-roster.csv"), Err(AppError::FileNotFound { .. })));
+    }
+
+    #[test]
+    fn validates_against_a_league_schema() {
+        let schema_path = write_fixture(
+            "league_schema.json",
+            r#"{ "columns": {
+                "Coach": { "allowed": ["Y", "N"] },
+                "Jersey Number": { "required": true, "type": "number" },
+                "Email": { "type": "email" },
+                "Shirt Size": { "required": true }
+            } }"#,
+        );
+        let content = "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Email\n\
+            1001,U10 Red,Ava,Smith,7,n,ava@example.com\n\
+            1002,U10 Red,Ben,Jones,,Maybe,\n\
+            1003,U10 Red,Cal,Lee,ten,Y,cal@\n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        let schema = load_schema(&schema_path).unwrap();
+        let issues = validate_with_schema(&data, &schema);
+        // Messages up to the offending value
+        let issues: Vec<(&str, &str)> = issues
+            .iter()
+            .map(|issue| (issue.barcode.as_str(), issue.message.split(':').next().unwrap()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                ("", "Missing required column \"Shirt Size\""),
+                ("1002", "Coach \"Maybe\" isn't one of"),
+                ("1002", "Missing Jersey Number"),
+                ("1003", "Invalid email address"),
+                ("1003", "Jersey Number must be a number"),
+            ]
+        );
+
+        // A misspelled rule is an error, not a silently ignored setting
+        let typo = write_fixture("league_schema_typo.json", r#"{ "columns": { "Email": { "requried": true } } }"#);
+        assert!(matches!(AppError::from(load_schema(&typo).unwrap_err()), AppError::Parse { .. }));
+    }
+
+    #[test]
+    fn summarizes_players_and_items() {
+        let content = "Barcode Number,Team,First Name,Last Name,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,\"810T,810T,5x7\",PKG-A\n\
+            1002,U10 Red,Ben,Jones,5x7,\n\
+            1003,U12 Blue,Cal,Lee,,PKG-A\n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        let summary = summarize_roster(&data.players);
+
+        assert_eq!(summary.total_players, 3);
+        assert_eq!(summary.players_per_team["U10 Red"], 2);
+        assert_eq!(summary.players_per_team["U12 Blue"], 1);
+        assert_eq!(summary.product_counts["810T"], 2);
+        assert_eq!(summary.product_counts["5x7"], 2);
+        assert_eq!(summary.package_counts["PKG-A"], 2);
+
+        let counts = count_teams(&data.players);
+        let counts: Vec<(&str, usize)> = counts.iter().map(|c| (c.team.as_str(), c.players)).collect();
+        assert_eq!(counts, vec![("U10 Red", 2), ("U12 Blue", 1)]);
+    }
+
+    #[test]
+    fn finds_players_without_orders() {
+        let content = "Barcode Number,Team,First Name,Last Name,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,810T,\n\
+            1002,U10 Red,Ben,Jones,,\n\
+            1003,U12 Blue,Cal,Lee,,PKG-A\n\
+            1004,U12 Blue,Dee,Moss,\"  \",\n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        let missing: Vec<&str> = data
+            .players
+            .iter()
+            .filter(|p| has_no_order(p))
+            .map(|p| p.barcode.as_str())
+            .collect();
+        assert_eq!(missing, vec!["1002", "1004"]);
+    }
+
+    #[test]
+    fn groups_players_sharing_contacts() {
+        let content = "Barcode Number,Team,First Name,Last Name,Cell Phone,Email\n\
+            1001,U10 Red,Ava,Smith,(555) 123-4567,Smith@Example.com\n\
+            1002,U12 Blue,Max,Smith,555.123.4567,smith@example.com\n\
+            1003,U10 Red,Cal,Lee,,\n\
+            1004,U10 Red,Dee,Moss,,\n\
+            1005,U10 Red,Eli,Ward,,ward@example.com\n\
+            1005,U10 Red,Eli,Ward,,ward@example.com\n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+
+        let groups = shared_contacts(&data.players);
+        let summary: Vec<(ContactKind, &str, Vec<&str>)> = groups
+            .iter()
+            .map(|g| (g.kind, g.contact.as_str(), g.players.iter().map(|p| p.barcode.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ContactKind::Email, "Smith@Example.com", vec!["1001", "1002"]),
+                (ContactKind::Phone, "(555) 123-4567", vec!["1001", "1002"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_single_fields_by_column_name() {
+        assert!(check_field("Email", "a@b.com").is_ok());
+        assert!(check_field("Email", "nope@").is_err());
+        assert!(check_field("Cell Phone", "555-123-4567").is_ok());
+        assert!(check_field("Cell Phone", "123").is_err());
+        assert_eq!(check_field("First Name", "  "), Err("Missing first name".to_string()));
+        // Columns without rules, extras included, always pass
+        assert!(check_field("Jersey Number", "").is_ok());
+        assert!(check_field("Photo Status", "").is_ok());
+    }
+
+    #[test]
+    fn validates_email_addresses() {
+        assert!(validate_email("a@b.com").is_ok());
+        assert!(validate_email("").is_ok());
+        assert!(validate_email("nope@").is_err());
+        assert!(validate_email("john@gmail").is_err());
+        assert!(validate_email("@b.com").is_err());
+        assert!(validate_email("a b@c.com").is_err());
+    }
+
+    #[test]
+    fn normalizes_phone_numbers() {
+        assert_eq!(normalize_phone("(555) 123-4567").unwrap(), "(555) 123-4567");
+        assert_eq!(normalize_phone("555.123.4567").unwrap(), "(555) 123-4567");
+        assert_eq!(normalize_phone("5551234567").unwrap(), "(555) 123-4567");
+        assert_eq!(normalize_phone("+1 555 123 4567").unwrap(), "(555) 123-4567");
+        assert_eq!(normalize_phone("").unwrap(), "");
+        assert!(normalize_phone("555-1234").is_err());
+        assert!(normalize_phone("25551234567").is_err());
+    }
+
+    #[test]
+    fn splits_order_items_losslessly() {
+        assert_eq!(split_order_items("810T,810T,5x7"), vec!["810T", "810T", "5x7"]);
+        assert_eq!(split_order_items(" A , B ,,"), vec!["A", "B"]);
+        assert!(split_order_items("").is_empty());
+
+        let content = "Barcode Number,Team,First Name,Last Name,Products,Packages\n\
+            1001,U10 Red,Ava,Smith,\"810T,5x7\",PKG-A\n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+        assert_eq!(data.players[0].product_items, vec!["810T", "5x7"]);
+        assert_eq!(data.players[0].package_items, vec!["PKG-A"]);
+        assert_eq!(render_csv_data(&data).unwrap(), content.as_bytes());
+    }
+
+    #[test]
+    fn parses_git_porcelain_status() {
+        let porcelain = " M 2024/u10_red.csv\nM  2024/u12_blue.csv\nA  2024/new.csv\n D old.csv\nR  a.csv -> b.csv\n?? notes.txt\n";
+
+        let status = parse_git_status(porcelain);
+
+        assert_eq!(
+            status,
+            GitStatus {
+                branch: String::new(),
+                modified: 3,
+                added: 1,
+                deleted: 1,
+                untracked: 1,
             }
+        );
+    }
 
-            Ok(())
-        })
-        .on_menu_event(|app, event| {
-            let id = event.id().as_ref();
-            println!("Menu item clicked: {}", id);
+    #[test]
+    fn recognizes_git_auth_failures() {
+        assert!(is_auth_failure(
+            "git@github.com: Permission denied (publickey).\nfatal: Could not read from remote repository."
+        ));
+        assert!(is_auth_failure("Host key verification failed.\nfatal: Could not read from remote repository."));
+        assert!(!is_auth_failure(
+            "ssh: Could not resolve hostname github.com: nodename nor servname provided, or not known"
+        ));
+    }
 
-            match id {
-                "open" => {
-                    let _ = app.emit("menu-open-file", ());
-                }
-                "update" => {
-                    let _ = app.emit("menu-update-app", ());
-                }
-                "pull" => {
-                    let _ = app.emit("menu-git-pull", ());
-                }
-                "push" => {
-                    let _ = app.emit("menu-git-push", ());
-                }
-                _ => {}
+    #[test]
+    fn player_photos_live_under_the_photos_folder() {
+        let parent = Path::new("/Volumes/Photos");
+        assert_eq!(
+            player_photos_dir(parent, " 00123 ").unwrap(),
+            Path::new("/Volumes/Photos/photos/00123")
+        );
+        for barcode in ["", "..", "../etc", "a\\b"] {
+            assert!(matches!(player_photos_dir(parent, barcode), Err(AppError::Validation { .. })));
+        }
+    }
+
+    #[test]
+    fn recognizes_missing_upstream() {
+        assert!(is_missing_upstream(
+            "fatal: The current branch picture-day has no upstream branch.\nTo push the current branch and set the remote as upstream, use\n\n    git push --set-upstream origin picture-day\n"
+        ));
+        assert!(!is_missing_upstream("! [rejected]        main -> main (fetch first)"));
+    }
+
+    #[test]
+    fn recent_files_are_newest_first_and_capped() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests");
+        let paths: Vec<String> = (0..12)
+            .map(|i| write_fixture(&format!("recent_{}.csv", i), "Barcode Number\n"))
+            .collect();
+
+        let mut recent = vec![dir.join("gone.csv").to_string_lossy().to_string()];
+        for path in &paths {
+            push_recent_file(&mut recent, path);
+        }
+        push_recent_file(&mut recent, &paths[5]);
+
+        assert_eq!(recent.len(), MAX_RECENT_FILES);
+        assert_eq!(recent[0], paths[5]);
+        assert_eq!(recent[1], paths[11]);
+        assert_eq!(recent.iter().filter(|p| **p == paths[5]).count(), 1);
+        assert!(!recent.iter().any(|p| p.ends_with("gone.csv")));
+    }
+
+    #[test]
+    fn read_only_mode_blocks_writes() {
+        let read_only = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        assert!(matches!(ensure_writable_with(&read_only), Err(AppError::ReadOnly { .. })));
+        assert!(ensure_writable_with(&Config::default()).is_ok());
+    }
+
+    #[test]
+    fn builds_push_pathspec() {
+        assert_eq!(push_pathspec(&[]).unwrap(), vec!["."]);
+        assert_eq!(
+            push_pathspec(&["2024/u10_red.csv".to_string(), "-odd.csv".to_string()]).unwrap(),
+            vec!["--", "2024/u10_red.csv", "-odd.csv"]
+        );
+        assert!(push_pathspec(&[" ".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_git_numstat() {
+        let numstat = "12\t3\t2024/u10_red.csv\n-\t-\tlogo.png\n0\t1\tnotes with spaces.txt\n";
+
+        let diffs = parse_numstat(numstat, true);
+
+        assert_eq!(diffs.len(), 3);
+        assert_eq!((diffs[0].added, diffs[0].removed), (Some(12), Some(3)));
+        assert_eq!(diffs[0].path, "2024/u10_red.csv");
+        assert_eq!((diffs[1].added, diffs[1].removed), (None, None));
+        assert_eq!(diffs[2].path, "notes with spaces.txt");
+        assert!(diffs.iter().all(|d| d.staged && !d.untracked));
+    }
+
+    #[test]
+    fn explains_checkout_failures() {
+        let dirty = explain_checkout_failure(
+            "error: Your local changes to the following files would be overwritten by checkout:\n\tu10.csv\n\
+             Please commit your changes or stash them before you switch branches.\nAborting\n",
+            "staging",
+        );
+        match dirty {
+            AppError::Git { message, suggestion, .. } => {
+                assert!(message.contains("staging"));
+                assert!(suggestion.unwrap().contains("discard"));
             }
-        })
-        .invoke_handler(tauri::generate_handler![
-            greet,
-            load_csv,
-            save_player,
-            create_backup,
-            write_csv_file,
-            run_update,
-            git_pull,
-            git_push,
-            get_barcodes_path
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            other => panic!("expected a git error, got {:?}", other),
+        }
+
+        let missing = explain_checkout_failure("error: pathspec 'stagin' did not match any file(s) known to git\n", "stagin");
+        assert_eq!(missing.message(), "There is no branch named stagin.");
+    }
+
+    #[test]
+    fn describes_sync_state() {
+        assert_eq!(parse_ahead_behind("1\t2\n"), Some((1, 2)));
+        assert_eq!(parse_ahead_behind(""), None);
+
+        let state = |ahead, behind, fetched| SyncState {
+            branch: "main".to_string(),
+            ahead,
+            behind,
+            fetched,
+            ..SyncState::default()
+        };
+        assert_eq!(describe_sync_state(&state(Some(0), Some(0), true)), "Up to date with remote");
+        assert_eq!(
+            describe_sync_state(&state(Some(0), Some(2), true)),
+            "2 commits behind remote — pull recommended"
+        );
+        assert_eq!(
+            describe_sync_state(&state(Some(1), Some(0), true)),
+            "1 commit ahead of remote — push to share"
+        );
+        assert!(describe_sync_state(&state(None, None, true)).contains("no remote branch"));
+        assert!(describe_sync_state(&state(Some(0), Some(0), false)).ends_with("may be out of date)"));
+    }
+
+    #[test]
+    fn describes_discarded_changes() {
+        let status = GitStatus {
+            modified: 2,
+            untracked: 1,
+            ..GitStatus::default()
+        };
+        assert_eq!(describe_discarded(&status), "Discarded 2 modified, 1 untracked file(s).");
+        assert!(describe_discarded(&GitStatus::default()).starts_with("Nothing to discard"));
+    }
+
+    #[test]
+    fn explains_common_pull_failures() {
+        let explain = |stdout: &str, stderr: &str, rebase: bool| match explain_pull_failure(stdout, stderr, rebase) {
+            AppError::Git { message, suggestion, raw } => (message, suggestion, raw),
+            other => panic!("expected a git error, got {:?}", other),
+        };
+
+        let (_, suggestion, raw) = explain(
+            "",
+            "error: Your local changes to the following files would be overwritten by merge:\n\tu10.csv\nAborting\n",
+            false,
+        );
+        assert_eq!(suggestion.unwrap(), "Push them first or discard them, then pull again.");
+        assert!(raw.unwrap().contains("u10.csv"));
+
+        let (message, _, _) = explain(
+            "Auto-merging u10.csv\nCONFLICT (content): Merge conflict in u10.csv\n",
+            "",
+            false,
+        );
+        assert!(message.contains("conflicts"));
+        assert!(!message.contains("rebase"));
+
+        let (message, _, _) = explain(
+            "Auto-merging u10.csv\nCONFLICT (content): Merge conflict in u10.csv\n",
+            "error: could not apply 1a2b3c4... Update U10\n",
+            true,
+        );
+        assert!(message.contains("rebase"));
+
+        let (message, suggestion, raw) = explain("", "fatal: something else\n", false);
+        assert_eq!(message, "Git pull failed.");
+        assert!(suggestion.is_none());
+        assert_eq!(raw.unwrap(), "fatal: something else");
+    }
+
+    #[test]
+    fn ranks_search_results() {
+        let player = |barcode: &str, first: &str, last: &str, jersey: &str| Player {
+            barcode: barcode.to_string(),
+            first_name: first.to_string(),
+            last_name: last.to_string(),
+            jersey_number: jersey.to_string(),
+            ..Player::default()
+        };
+        let players = vec![
+            player("1001", "Samantha", "Lee", "3"),
+            player("1002", "Sam", "Ortiz", "12"),
+            player("1003", "Ben", "Samuels", "7"),
+            player("1004", "Cal", "Diaz", "9"),
+        ];
+
+        let barcodes = |results: Vec<Player>| -> Vec<String> {
+            results.into_iter().map(|p| p.barcode).collect()
+        };
+
+        assert_eq!(barcodes(rank_players(players.clone(), "SAM")), vec!["1002", "1001", "1003"]);
+        assert_eq!(barcodes(rank_players(players.clone(), "smth")), vec!["1001"]);
+        assert_eq!(barcodes(rank_players(players.clone(), "1004")), vec!["1004"]);
+        assert_eq!(rank_players(players, "  ").len(), 4);
+    }
+
+    #[test]
+    fn sorts_players_by_last_then_first_name() {
+        let content = "Barcode Number,Team,First Name,Last Name\n\
+            1001,U10 Red,Ben,smith\n\
+            1002,U10 Red,,Adams\n\
+            1003,U12 Blue,Ava,Smith\n\
+            1004,U12 Blue,Cal,\n\
+            1005,U12 Blue,Dee,adams\n";
+        let mut players = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap().players;
+        players.sort_by(compare_player_names);
+
+        let barcodes: Vec<&str> = players.iter().map(|p| p.barcode.as_str()).collect();
+        assert_eq!(barcodes, vec!["1005", "1002", "1003", "1001", "1004"]);
+    }
+
+    #[test]
+    fn sorts_jersey_numbers_numerically() {
+        let mut jerseys = vec!["10", "", "2A", "C", "2", "03", "11", "b", "2a"];
+        jerseys.sort_by(|a, b| compare_jersey_numbers(a, b));
+
+        assert_eq!(jerseys, vec!["2", "2A", "2a", "03", "10", "11", "b", "C", ""]);
+    }
+
+    fn write_fixture(name: &str, content: &str) -> String {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn cached_roster_is_refreshed_after_a_write() {
+        let path = write_fixture(
+            "cache.csv",
+            "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n",
+        );
+
+        let first = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(first.players[0].first_name, "Ava");
+
+        write_atomic(Path::new(&path), b"Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Eva,Smith\n").unwrap();
+
+        let second = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(second.players[0].first_name, "Eva");
+    }
+
+    #[test]
+    fn session_stats_accumulate_from_one_start_time() {
+        let stats = Mutex::new(SessionStats::new());
+        let started_at = stats.lock().unwrap().started_at;
+        record_session(&stats, |stats| stats.edited += 2);
+        record_session(&stats, |stats| stats.added += 1);
+        let after = *stats.lock().unwrap();
+        assert_eq!((after.edited, after.added, after.deleted), (2, 1, 0));
+        assert_eq!(after.started_at, started_at);
+    }
+
+    #[test]
+    fn recognizes_files_held_open_on_windows() {
+        for code in [5, 32, 33] {
+            assert!(is_file_in_use(&std::io::Error::from_raw_os_error(code), true));
+            assert!(!is_file_in_use(&std::io::Error::from_raw_os_error(code), false));
+        }
+        assert!(!is_file_in_use(&std::io::Error::from_raw_os_error(2), true));
+        assert!(!is_file_in_use(&std::io::Error::from(std::io::ErrorKind::PermissionDenied), true));
+    }
+
+    #[test]
+    fn file_in_use_message_reaches_the_save_error() {
+        // What write_csv_data hands back when Excel has the roster open
+        let rename_error = file_in_use_error(std::io::Error::from_raw_os_error(5), "roster.csv", true);
+        let err = anyhow::Error::new(rename_error).context("Failed to write CSV file: C:\\Rosters\\roster.csv");
+        let AppError::Io { message } = AppError::from(err) else {
+            panic!("expected an Io error");
+        };
+        assert_eq!(
+            message,
+            "Failed to write CSV file: C:\\Rosters\\roster.csv: roster.csv is open in another program. \
+             Close it there and try again; the file was not changed"
+        );
+
+        let other = file_in_use_error(std::io::Error::from_raw_os_error(5), "roster.csv", false);
+        assert!(!other.to_string().contains("open in another program"));
+    }
+
+    #[test]
+    fn write_atomic_replaces_file_without_leaving_temp_files() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests").join("atomic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("roster.csv");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let entries: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn verify_backup_rejects_truncated_copies() {
+        let source = write_fixture("verify_source.csv", "Barcode Number,Team\n1001,U10 Red\n");
+        let good = write_fixture("verify_good.csv", "Barcode Number,Team\n1001,U10 Red\n");
+        let truncated = write_fixture("verify_truncated.csv", "Barcode Number,Te");
+
+        assert!(verify_backup(Path::new(&source), Path::new(&good)).is_ok());
+        let err = verify_backup(Path::new(&source), Path::new(&truncated)).unwrap_err();
+        assert!(matches!(AppError::from(err), AppError::Io { .. }));
+    }
+
+    #[test]
+    fn diffs_two_roster_snapshots() {
+        let before = parse_csv_content(
+            "Barcode Number,Team,First Name,Last Name,Notes\n\
+             1001,U10 Red,Ava,Smith,\n1002,U10 Red,Ben,Jones,\n1003,U12 Blue,Cal,Lee,\n",
+            "before.csv",
+            &mut |_, _| {},
+        )
+        .unwrap();
+        let after = parse_csv_content(
+            "Barcode Number,Team,First Name,Last Name,Notes\n\
+             1001,U12 Blue,Ava,Smith,moved\n1003,U12 Blue,Cal,Lee,\n1004,U12 Blue,Dee,Moss,\n",
+            "after.csv",
+            &mut |_, _| {},
+        )
+        .unwrap();
+
+        let diffs = diff_players(&before.players, &after.players);
+
+        let summary: Vec<(&str, &DiffKind)> = diffs.iter().map(|d| (d.barcode.as_str(), &d.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![("1001", &DiffKind::Changed), ("1002", &DiffKind::Removed), ("1004", &DiffKind::Added)]
+        );
+        let fields: Vec<(&str, &str, &str)> = diffs[0]
+            .fields
+            .iter()
+            .map(|f| (f.field.as_str(), f.old_value.as_str(), f.new_value.as_str()))
+            .collect();
+        assert_eq!(fields, vec![("Team", "U10 Red", "U12 Blue"), ("Notes", "", "moved")]);
+    }
+
+    #[test]
+    fn prune_backups_keeps_newest_and_ignores_other_files() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests").join("prune");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let roster = dir.join("roster.csv");
+        for name in [
+            "roster.csv",
+            "roster_backup_20240518_090000.csv",
+            "roster_backup_20240518_100000.csv",
+            "roster_backup_20240518_110000.csv",
+            "roster_backup_notes.csv",
+            "roster_backup_20240518_080000.csv.txt",
+            "other_backup_20240518_070000.csv",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        prune_backups(&roster, 2).unwrap();
+
+        let mut remaining: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "other_backup_20240518_070000.csv",
+                "roster.csv",
+                "roster_backup_20240518_080000.csv.txt",
+                "roster_backup_20240518_100000.csv",
+                "roster_backup_20240518_110000.csv",
+                "roster_backup_notes.csv",
+            ]
+        );
+    }
+
+    #[test]
+    fn archive_copies_roster_and_backups_into_a_dated_folder() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests").join("archive_source");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["roster.csv", "roster_backup_20240518_090000.csv", "other.csv"] {
+            fs::write(dir.join(name), name).unwrap();
+        }
+
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 5, 18).unwrap();
+        let roster = dir.join("roster.csv").to_string_lossy().to_string();
+        let archive = tauri::async_runtime::block_on(archive_roster_files(&roster, date)).unwrap();
+        assert_eq!(Path::new(&archive), dir.join("archive").join("2024-05-18"));
+
+        let mut archived: Vec<String> = fs::read_dir(&archive)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        archived.sort();
+        assert_eq!(archived, vec!["roster.csv", "roster_backup_20240518_090000.csv"]);
+        assert!(dir.join("roster.csv").exists());
+    }
+
+    #[test]
+    fn backup_usage_sums_matching_backups() {
+        let dir = std::env::temp_dir().join("mvs-photo-form-tests").join("usage");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, size) in [
+            ("roster.csv", 50),
+            ("roster_backup_20240518_090000.csv", 10),
+            ("roster_backup_20240518_100000.csv", 30),
+            ("roster_backup_notes.csv", 99),
+        ] {
+            fs::write(dir.join(name), vec![b'x'; size]).unwrap();
+        }
+
+        let usage = backup_usage(&dir.join("roster.csv").to_string_lossy()).unwrap();
+        assert_eq!(usage.total_bytes, 40);
+        let sizes: Vec<u64> = usage.backups.iter().map(|b| b.bytes).collect();
+        assert_eq!(sizes, vec![30, 10]);
+    }
+
+    #[test]
+    fn save_player_moves_player_to_new_team() {
+        let path = write_fixture(
+            "move_team.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n\
+             1002,U10 Red,Ben,Jones,12,N,,,,\n",
+        );
+
+        let update = PlayerUpdate {
+            barcode: "1002".to_string(),
+            team: "U12 Blue".to_string(),
+            first_name: "Ben".to_string(),
+            last_name: "Jones".to_string(),
+            jersey_number: "4".to_string(),
+            cell_phone: "555.123.4567".to_string(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        let result = tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+        assert_eq!(result.player.cell_phone, "(555) 123-4567");
+        assert_eq!(result.teams, vec!["U10 Red", "U12 Blue"]);
+
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.teams, vec!["U10 Red", "U12 Blue"]);
+        let ben = data.players.iter().find(|p| p.barcode == "1002").unwrap();
+        assert_eq!(ben.team, "U12 Blue");
+        assert_eq!(ben.jersey_number, "4");
+
+        // Moving the last U10 Red player drops that team
+        let update = PlayerUpdate {
+            barcode: "1001".to_string(),
+            team: "U12 Blue".to_string(),
+            first_name: "Ava".to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: "7".to_string(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        let result = tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+        assert_eq!(result.teams, vec!["U12 Blue"]);
+    }
+
+    #[test]
+    fn save_player_asks_before_creating_a_lookalike_team() {
+        let path = write_fixture(
+            "lookalike_team.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n\
+             1002,U12 Blue,Ben,Jones,12,N,,,,\n",
+        );
+        let update = |team: &str, confirm_new_team: bool| PlayerUpdate {
+            barcode: "1002".to_string(),
+            team: team.to_string(),
+            first_name: "Ben".to_string(),
+            last_name: "Jones".to_string(),
+            jersey_number: "12".to_string(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team,
+        };
+
+        for typo in ["U10 Rde", "u10  red"] {
+            let err = AppError::from(
+                tauri::async_runtime::block_on(save_player_data(&path, update(typo, false))).unwrap_err(),
+            );
+            match err {
+                AppError::NeedsConfirmation { message, .. } => assert!(message.contains("\"U10 Red\"")),
+                other => panic!("expected a confirmation prompt, got {:?}", other),
+            }
+        }
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.teams, vec!["U10 Red", "U12 Blue"]);
+
+        // A different age group is a real team, not a typo
+        tauri::async_runtime::block_on(save_player_data(&path, update("U14 Red", false))).unwrap();
+
+        let result = tauri::async_runtime::block_on(save_player_data(&path, update("U10 Rde", true))).unwrap();
+        assert_eq!(result.teams, vec!["U10 Rde", "U10 Red"]);
+    }
+
+    #[test]
+    fn save_player_records_changed_fields_in_edit_log() {
+        let path = write_fixture(
+            "edit_log.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n",
+        );
+        let _ = fs::remove_file(edit_log_path(Path::new(&path)));
+
+        let update = PlayerUpdate {
+            barcode: "1001".to_string(),
+            team: "U10 Red".to_string(),
+            first_name: "Ava".to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: "9".to_string(),
+            cell_phone: String::new(),
+            email: "ava@example.com".to_string(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+
+        let log = read_edit_log(Path::new(&path)).unwrap();
+        let changes: Vec<(&str, &str, &str)> = log
+            .iter()
+            .map(|e| (e.field.as_str(), e.old_value.as_str(), e.new_value.as_str()))
+            .collect();
+        assert_eq!(changes, vec![("Jersey Number", "7", "9"), ("Email", "", "ava@example.com")]);
+        assert!(log.iter().all(|e| e.barcode == "1001"));
+    }
+
+    #[test]
+    fn trims_typed_fields_on_load_and_save() {
+        let content = "Barcode Number,Team,First Name,Last Name,Email,Notes\n\
+            1001 , U10 Red,  Ava , Van Der Berg , ava@example.com , keep me \n";
+        let data = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap();
+        let player = &data.players[0];
+        assert_eq!(player.barcode, "1001");
+        assert_eq!(player.team, "U10 Red");
+        assert_eq!(player.first_name, "Ava");
+        assert_eq!(player.last_name, "Van Der Berg");
+        assert_eq!(player.email, "ava@example.com");
+        assert_eq!(player.value("Notes"), " keep me ");
+
+        let path = write_fixture(
+            "trim_save.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n",
+        );
+        let update = PlayerUpdate {
+            barcode: " 1001".to_string(),
+            team: "U10 Red ".to_string(),
+            first_name: " Mary Jo ".to_string(),
+            last_name: "Van Der Berg  ".to_string(),
+            jersey_number: "7".to_string(),
+            cell_phone: String::new(),
+            email: " ava@example.com ".to_string(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        let result = tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+        assert_eq!(result.player.first_name, "Mary Jo");
+        assert_eq!(result.player.last_name, "Van Der Berg");
+        assert_eq!(result.player.email, "ava@example.com");
+    }
+
+    #[test]
+    fn leading_zero_barcodes_round_trip() {
+        let path = write_fixture(
+            "leading_zeros.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             00123,U10 Red,Ava,Smith,7,N,,,,\n\
+             123,U10 Red,Ben,Jones,8,N,,,,\n",
+        );
+        let update = PlayerUpdate {
+            barcode: "00123".to_string(),
+            team: "U10 Red".to_string(),
+            first_name: "Ava".to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: "9".to_string(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.contains("\n00123,U10 Red,Ava,Smith,9,"));
+        let player = tauri::async_runtime::block_on(get_player(path.clone(), "00123".to_string())).unwrap();
+        assert_eq!((player.barcode.as_str(), player.jersey_number.as_str()), ("00123", "9"));
+        let other = tauri::async_runtime::block_on(get_player(path, "123".to_string())).unwrap();
+        assert_eq!((other.first_name.as_str(), other.jersey_number.as_str()), ("Ben", "8"));
+    }
+
+    #[test]
+    fn lowercase_scans_match_uppercase_barcodes_when_enabled() {
+        let content = "Barcode Number,Team,First Name,Last Name\nAB123,U10 Red,Ava,Smith\n00123,U10 Red,Ben,Jones\n";
+        let players = parse_csv_content(content, "roster.csv", &mut |_, _| {}).unwrap().players;
+        let find = |scan: &str, ignore_case: bool| {
+            players
+                .iter()
+                .find(|p| same_barcode(&p.barcode, scan, ignore_case))
+                .map(|p| p.first_name.as_str())
+        };
+
+        assert_eq!(find("ab123", true), Some("Ava"));
+        assert_eq!(find(" ab123 ", true), Some("Ava"));
+        // Off by default, and either way leading zeros still matter
+        assert_eq!(find("ab123", false), None);
+        assert_eq!(find("AB123", false), Some("Ava"));
+        assert_eq!(find("123", true), None);
+        assert!(!Config::default().case_insensitive_barcodes);
+    }
+
+    #[test]
+    fn players_without_barcodes_match_on_the_fallback_column() {
+        let path = write_fixture(
+            "order_ids.csv",
+            "Barcode Number,Team,First Name,Last Name,Order ID\n\
+             ,U10 Red,Ava,Smith,ORD-17\n\
+             1002,U10 Red,Ben,Jones,ORD-18\n",
+        );
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        let (ava, ben) = (&data.players[0], &data.players[1]);
+
+        assert!(matches_fallback_key(ava, "Order ID", " ORD-17 "));
+        assert!(!matches_fallback_key(ava, "Order ID", "ORD-18"));
+        assert!(!matches_fallback_key(ava, "Order ID", ""));
+        // Once a barcode is assigned, that's the only key
+        assert!(!matches_fallback_key(ben, "Order ID", "ORD-18"));
+        assert_eq!(Config::default().fallback_match_column, None);
+    }
+
+    #[test]
+    fn preview_save_renders_without_writing() {
+        let original = "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n";
+        let path = write_fixture("preview_save.csv", original);
+        let update = PlayerUpdate {
+            barcode: "1001".to_string(),
+            team: "U10 Red".to_string(),
+            first_name: "Ava".to_string(),
+            last_name: "Smith-Jones".to_string(),
+            jersey_number: "7".to_string(),
+            cell_phone: "5551234567".to_string(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+
+        let preview = tauri::async_runtime::block_on(preview_save_data(&path, update)).unwrap();
+        assert_eq!(
+            preview,
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith-Jones,7,N,(555) 123-4567,,,\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn save_player_writes_extra_columns() {
+        let path = write_fixture(
+            "extra_fields.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages,Photo Status\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,,Pending\n",
+        );
+        let update = |extra_fields: HashMap<String, String>| PlayerUpdate {
+            barcode: "1001".to_string(),
+            team: "U10 Red".to_string(),
+            first_name: "Ava".to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: "7".to_string(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields,
+            confirm_new_team: false,
+        };
+
+        let extra = HashMap::from([("Photo Status".to_string(), "Done".to_string())]);
+        tauri::async_runtime::block_on(save_player_data(&path, update(extra))).unwrap();
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players[0].value("Photo Status"), "Done");
+
+        // Typed columns go through their own fields, unknown columns have nowhere to land
+        for column in ["Team", "Retake"] {
+            let extra = HashMap::from([(column.to_string(), "x".to_string())]);
+            let err = tauri::async_runtime::block_on(save_player_data(&path, update(extra))).unwrap_err();
+            assert!(matches!(AppError::from(err), AppError::Validation { .. }));
+        }
+    }
+
+    #[test]
+    fn drafts_are_kept_per_barcode_until_cleared() {
+        let path = std::env::temp_dir().join("mvs-photo-form-tests").join("drafts").join(DRAFTS_FILE_NAME);
+        let _ = fs::remove_file(&path);
+        let draft = |barcode: &str, first_name: &str| PlayerUpdate {
+            barcode: barcode.to_string(),
+            team: "U10 Red".to_string(),
+            first_name: first_name.to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: String::new(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: "810T".to_string(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+
+        tauri::async_runtime::block_on(async {
+            store_draft(&path, draft("1001", "Av")).await.unwrap();
+            store_draft(&path, draft("1001", "Ava")).await.unwrap();
+            store_draft(&path, draft("1002", "Bo")).await.unwrap();
+            remove_draft(&path, "1002").await.unwrap();
+        });
+
+        let drafts = read_drafts(&path).unwrap();
+        assert_eq!(drafts.keys().collect::<Vec<_>>(), ["1001"]);
+        assert_eq!(drafts["1001"].first_name, "Ava");
+        assert_eq!(drafts["1001"].products, "810T");
+
+        let err = tauri::async_runtime::block_on(store_draft(&path, draft(" ", "Ava"))).unwrap_err();
+        assert!(matches!(AppError::from(err), AppError::Validation { .. }));
+    }
+
+    #[test]
+    fn restores_the_last_good_copy() {
+        let path = write_fixture("last_good.csv", "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n");
+        let _ = fs::remove_file(last_good_path(Path::new(&path)));
+        let missing = tauri::async_runtime::block_on(restore_last_good_file(&path)).unwrap_err();
+        assert!(matches!(AppError::from(missing), AppError::NotFound { .. }));
+
+        let good = "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Jones\n";
+        tauri::async_runtime::block_on(write_csv_content(&path, good.to_string(), None)).unwrap();
+        assert_eq!(fs::read_to_string(last_good_path(Path::new(&path))).unwrap(), good);
+
+        fs::write(&path, "Barcode Number,Team,Fir").unwrap();
+        tauri::async_runtime::block_on(restore_last_good_file(&path)).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), good);
+    }
+
+    #[test]
+    fn export_team_writes_only_that_team() {
+        let path = write_fixture(
+            "export_source.csv",
+            "Team;Barcode Number;First Name;Last Name;Notes\n\
+             U10 Red;1001;Ava;Smith;early\n\
+             U12 Blue;1002;Ben;Jones;\n\
+             U10 Red;1003;Cal;Lee;late\n",
+        );
+        let out_path = std::env::temp_dir().join("mvs-photo-form-tests").join("export_u10.csv");
+        let _ = fs::remove_file(&out_path);
+        let out_path = out_path.to_string_lossy().to_string();
+
+        let result = tauri::async_runtime::block_on(export_team_data(&path, "U10 Red", &out_path)).unwrap();
+        assert!(result.changed);
+
+        let exported = fs::read_to_string(&out_path).unwrap();
+        assert_eq!(
+            exported,
+            "Team;Barcode Number;First Name;Last Name;Notes\nU10 Red;1001;Ava;Smith;early\nU10 Red;1003;Cal;Lee;late\n"
+        );
+
+        let missing = tauri::async_runtime::block_on(export_team_data(&path, "U14 Green", &out_path));
+        assert!(matches!(AppError::from(missing.unwrap_err()), AppError::NotFound { .. }));
+    }
+
+    #[test]
+    fn export_labels_uses_the_printer_column_order() {
+        let path = write_fixture(
+            "labels_source.csv",
+            "Team;Barcode Number;First Name;Last Name;Jersey Number;Packages;Notes\n\
+             U10 Red;1001;Ava;Smith;7;A;early\n\
+             U12 Blue;1002;Ben;Jones;;B, C;\n",
+        );
+        let out_path = std::env::temp_dir().join("mvs-photo-form-tests").join("labels.csv");
+        let _ = fs::remove_file(&out_path);
+        let out_path = out_path.to_string_lossy().to_string();
+
+        let result = tauri::async_runtime::block_on(export_labels_data(&path, &out_path)).unwrap();
+        assert!(result.changed);
+        assert_eq!(
+            fs::read_to_string(&out_path).unwrap(),
+            "Barcode Number,Last Name,First Name,Jersey Number,Team,Packages\n\
+             1001,Smith,Ava,7,U10 Red,A\n\
+             1002,Jones,Ben,,U12 Blue,\"B, C\"\n"
+        );
+
+        let again = tauri::async_runtime::block_on(export_labels_data(&path, &out_path)).unwrap();
+        assert!(!again.changed);
+    }
+
+    #[test]
+    fn export_json_includes_extra_columns() {
+        let path = write_fixture(
+            "export_json_source.csv",
+            "Barcode Number,Team,First Name,Last Name,Photo Status\n1001,U10 Red,Ava,Smith,Done\n",
+        );
+        let out_path = std::env::temp_dir().join("mvs-photo-form-tests").join("export.json");
+        let _ = fs::remove_file(&out_path);
+        let out_path = out_path.to_string_lossy().to_string();
+
+        let result = tauri::async_runtime::block_on(export_json_data(&path, &out_path)).unwrap();
+        assert!(result.changed);
+
+        let exported: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(&out_path).unwrap()).unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0]["Barcode Number"], "1001");
+        assert_eq!(exported[0]["Photo Status"], "Done");
+
+        let over_roster = tauri::async_runtime::block_on(export_json_data(&path, &path));
+        assert!(matches!(AppError::from(over_roster.unwrap_err()), AppError::Validation { .. }));
+        assert!(fs::read_to_string(&path).unwrap().starts_with("Barcode Number,"));
+    }
+
+    #[test]
+    fn import_json_updates_and_adds_by_barcode() {
+        let path = write_fixture(
+            "import_json.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n\
+             1002,U12 Blue,Ben,Jones,4,N,,,,\n",
+        );
+        let player = |barcode: &str, jersey: &str| {
+            serde_json::json!({
+                "Barcode Number": barcode, "Team": "U10 Red", "First Name": "Ava", "Last Name": "Smith",
+                "Jersey Number": jersey, "Coach": "N", "Cell Phone": "", "Email": "", "Products": "810T",
+                "Packages": "", "Photo Status": "Done"
+            })
+        };
+        let json_path = write_fixture(
+            "import_players.json",
+            &serde_json::to_string(&[player("1001", "9"), player("1003", "2")]).unwrap(),
+        );
+
+        let result = tauri::async_runtime::block_on(import_json_data(&path, &json_path)).unwrap();
+        assert_eq!(
+            result,
+            ImportResult {
+                added: 1,
+                updated: 1,
+                unchanged: 0,
+                changed: true,
+            }
+        );
+
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert!(data.headers.contains(&"Photo Status".to_string()));
+        let barcodes: Vec<&str> = data.players.iter().map(|p| p.barcode.as_str()).collect();
+        assert_eq!(barcodes, vec!["1001", "1002", "1003"]);
+        assert_eq!(data.players[0].jersey_number, "9");
+        assert_eq!(data.players[0].product_items, vec!["810T"]);
+        assert_eq!(data.players[2].value("Photo Status"), "Done");
+        assert_eq!(data.players[1].value("Photo Status"), "");
+    }
+
+    #[test]
+    fn import_json_keeps_typed_extra_fields_as_text() {
+        let path = write_fixture(
+            "import_json_scalars.csv",
+            "Barcode Number,Team,First Name,Last Name,Photo Count,Paid,Notes\n1001,U10 Red,Ava,Smith,0,false,x\n",
+        );
+        let import = |extras: serde_json::Value| {
+            let mut player = serde_json::json!({
+                "Barcode Number": "1001", "Team": "U10 Red", "First Name": "Ava", "Last Name": "Smith",
+                "Jersey Number": "", "Coach": "", "Cell Phone": "", "Email": "",
+                "Products": "", "Packages": ""
+            });
+            player.as_object_mut().unwrap().extend(extras.as_object().unwrap().clone());
+            let json_path = write_fixture("import_players_scalars.json", &serde_json::json!([player]).to_string());
+            tauri::async_runtime::block_on(import_json_data(&path, &json_path))
+        };
+
+        import(serde_json::json!({ "Photo Count": 2, "Paid": true, "Notes": null })).unwrap();
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players[0].value("Photo Count"), "2");
+        assert_eq!(data.players[0].value("Paid"), "true");
+        assert_eq!(data.players[0].value("Notes"), "");
+
+        let nested = import(serde_json::json!({ "Notes": ["a", "b"] }));
+        assert!(matches!(AppError::from(nested.unwrap_err()), AppError::Parse { .. }));
+    }
+
+    #[test]
+    fn import_json_cleans_players_like_a_save() {
+        let path = write_fixture(
+            "import_json_clean.csv",
+            "Barcode Number,Team,First Name,Last Name,Cell Phone,Email\n1001,U10 Red,Ava,Smith,,\n",
+        );
+        let import = |email: &str| {
+            let json = serde_json::json!([{
+                "Barcode Number": " 1001 ", "Team": "U10 Red ", "First Name": "Eva", "Last Name": "Smith",
+                "Jersey Number": "", "Coach": "", "Cell Phone": "555.123.4567", "Email": email,
+                "Products": "", "Packages": ""
+            }]);
+            let json_path = write_fixture("import_players_clean.json", &json.to_string());
+            tauri::async_runtime::block_on(import_json_data(&path, &json_path))
+        };
+
+        let rejected = import("eva@");
+        assert!(matches!(AppError::from(rejected.unwrap_err()), AppError::Validation { .. }));
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players[0].first_name, "Ava");
+
+        // " 1001 " is the existing player, not a second one
+        let result = import("eva@example.com").unwrap();
+        assert_eq!((result.added, result.updated), (0, 1));
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players.len(), 1);
+        assert_eq!(data.players[0].team, "U10 Red");
+        assert_eq!(data.players[0].cell_phone, "(555) 123-4567");
+    }
+
+    #[test]
+    fn merge_rosters_adds_new_barcodes_and_reports_conflicts() {
+        let base = write_fixture(
+            "merge_base.csv",
+            "Barcode Number,Team,First Name,Last Name\n1001,U10 Red,Ava,Smith\n1002,U12 Blue,Ben,Jones\n",
+        );
+        let incoming = write_fixture(
+            "merge_incoming.csv",
+            "Barcode Number,Team,First Name,Last Name\n\
+             1001,U10 Red,Ava,Smith\n\
+             1002,U12 Blue,Benny,Jones\n\
+             1003,U14 Green,Cal,Lee\n",
+        );
+
+        let result = tauri::async_runtime::block_on(merge_rosters_data(&base, &incoming)).unwrap();
+        assert_eq!(
+            result,
+            MergeResult {
+                added: 1,
+                skipped: 1,
+                conflicts: vec!["1002".to_string()],
+                changed: true,
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(&base).unwrap(),
+            "Barcode Number,Team,First Name,Last Name\n\
+             1001,U10 Red,Ava,Smith\n1002,U12 Blue,Ben,Jones\n1003,U14 Green,Cal,Lee\n"
+        );
+
+        // Merging the same file again has nothing left to add
+        let again = tauri::async_runtime::block_on(merge_rosters_data(&base, &incoming)).unwrap();
+        assert_eq!((again.added, again.skipped, again.changed), (0, 2, false));
+    }
+
+    #[test]
+    fn remap_barcodes_applies_mapping_and_refuses_duplicates() {
+        let path = write_fixture(
+            "remap_roster.csv",
+            "Barcode Number,Team,First Name,Last Name\n\
+             1001,U10 Red,Ava,Smith\n\
+             1002,U10 Red,Ben,Jones\n\
+             1003,U12 Blue,Cal,Lee\n",
+        );
+        let mapping = write_fixture("remap_mapping.csv", "Old,New\n1001,2001\n1002,2002\n9999,3000\n");
+
+        let result = tauri::async_runtime::block_on(remap_barcodes_data(&path, &mapping)).unwrap();
+        assert_eq!(
+            result,
+            RemapResult {
+                remapped: 2,
+                unmatched_mapping: vec!["9999".to_string()],
+                unmapped_players: vec!["1003".to_string()],
+                changed: true,
+            }
+        );
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        let barcodes: Vec<&str> = data.players.iter().map(|p| p.barcode.as_str()).collect();
+        assert_eq!(barcodes, vec!["2001", "2002", "1003"]);
+
+        let clash = write_fixture("remap_clash.csv", "Old,New\n2001,1003\n");
+        let err = tauri::async_runtime::block_on(remap_barcodes_data(&path, &clash)).unwrap_err();
+        assert!(matches!(AppError::from(err), AppError::Validation { .. }));
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players[0].barcode, "2001");
+    }
+
+    #[test]
+    fn normalize_teams_folds_spelling_variants() {
+        let path = write_fixture(
+            "normalize_teams.csv",
+            "Barcode Number,Team,First Name,Last Name
+             1001,u10 RED,Ava,Smith
+             1002,U10  Red,Ben,Jones
+             1003,U10 Red,Cal,Lee
+             1004,U12 Blue,Dee,Moss
+",
+        );
+
+        let merges = tauri::async_runtime::block_on(normalize_teams_data(&path, true)).unwrap();
+        assert_eq!(
+            merges,
+            vec![TeamMerge {
+                canonical: "U10 Red".to_string(),
+                variants: vec!["U10  Red".to_string(), "U10 Red".to_string(), "u10 RED".to_string()],
+                players: 3,
+            }]
+        );
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.teams, vec!["U10 Red", "U12 Blue"]);
+
+        // Already clean: nothing to report and no backup taken
+        assert!(tauri::async_runtime::block_on(normalize_teams_data(&path, true)).unwrap().is_empty());
+        assert_eq!(normalize_team_name(" u10  RED ", false), "u10 RED");
+    }
+
+    #[test]
+    fn fix_encoding_repairs_only_double_encoded_fields() {
+        let path = write_fixture(
+            "mojibake.csv",
+            "Barcode Number,Team,First Name,Last Name,Notes\n\
+             1001,U10 Red,JosÃ©,NÃºÃ±ez,donâ€™t crop\n\
+             1002,U10 Red,José,Muñoz,Ã\n\
+             1003,U12 Blue,Ben,Jones,\n",
+        );
+
+        let flagged = tauri::async_runtime::block_on(detect_encoding_issues(path.clone())).unwrap();
+        assert_eq!(flagged.iter().map(|p| p.barcode.as_str()).collect::<Vec<_>>(), vec!["1001"]);
+
+        let fixed = tauri::async_runtime::block_on(fix_encoding_data(&path)).unwrap();
+        assert_eq!(fixed.len(), 1);
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        let jose = &data.players[0];
+        assert_eq!((jose.first_name.as_str(), jose.last_name.as_str()), ("José", "Núñez"));
+        assert_eq!(jose.value("Notes"), "don\u{2019}t crop");
+        // Correct accents and a stray lone character stay as they were
+        let other = &data.players[1];
+        assert_eq!((other.first_name.as_str(), other.last_name.as_str()), ("José", "Muñoz"));
+        assert_eq!(other.value("Notes"), "Ã");
+
+        assert!(tauri::async_runtime::block_on(fix_encoding_data(&path)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn duplicate_player_copies_details_under_new_barcode() {
+        let path = write_fixture(
+            "duplicate_player.csv",
+            "Barcode Number,Team,First Name,Last Name,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,810T,PKG-A\n\
+             1002,U12 Blue,Ben,Jones,,\n",
+        );
+
+        let copy = tauri::async_runtime::block_on(duplicate_player_data(&path, "1001", "1005")).unwrap();
+        assert_eq!(copy.barcode, "1005");
+        assert_eq!(copy.package_items, vec!["PKG-A"]);
+
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        let barcodes: Vec<&str> = data.players.iter().map(|p| p.barcode.as_str()).collect();
+        assert_eq!(barcodes, vec!["1001", "1005", "1002"]);
+        assert_eq!(data.players[1].products, "810T");
+
+        let taken = tauri::async_runtime::block_on(duplicate_player_data(&path, "1001", "1002"));
+        assert!(matches!(AppError::from(taken.unwrap_err()), AppError::Validation { .. }));
+    }
+
+    #[test]
+    fn undo_last_edit_restores_previous_values() {
+        let path = write_fixture(
+            "undo_edit.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n",
+        );
+
+        let update = PlayerUpdate {
+            barcode: "1001".to_string(),
+            team: "U10 Red".to_string(),
+            first_name: "Eva".to_string(),
+            last_name: "Smith".to_string(),
+            jersey_number: "7".to_string(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: "N".to_string(),
+            products: "810".to_string(),
+            packages: String::new(),
+            extra_fields: HashMap::new(),
+            confirm_new_team: false,
+        };
+        tauri::async_runtime::block_on(save_player_data(&path, update)).unwrap();
+
+        let restored = tauri::async_runtime::block_on(undo_last_edit_data(&path)).unwrap();
+        assert_eq!(restored.first_name, "Ava");
+        // The undo is the last good write now, not the save it reverted
+        assert_eq!(
+            fs::read_to_string(last_good_path(Path::new(&path))).unwrap(),
+            fs::read_to_string(&path).unwrap()
+        );
+
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players[0].first_name, "Ava");
+        assert_eq!(data.players[0].products, "");
+        assert!(tauri::async_runtime::block_on(undo_last_edit_data(&path)).is_err());
+    }
+
+    #[test]
+    fn undo_restores_a_player_matched_on_the_fallback_column() {
+        let path = write_fixture(
+            "undo_order_ids.csv",
+            "Barcode Number,Team,First Name,Last Name,Order ID\n\
+             ,U10 Red,Ava,Smith,ORD-17\n\
+             ,U10 Red,Ben,Jones,ORD-18\n",
+        );
+        let settings = Config {
+            fallback_match_column: Some("Order ID".to_string()),
+            ..Config::default()
+        };
+        let update = |extra_fields: HashMap<String, String>| PlayerUpdate {
+            barcode: String::new(),
+            team: "U10 Red".to_string(),
+            first_name: "Benji".to_string(),
+            last_name: "Jones".to_string(),
+            jersey_number: String::new(),
+            cell_phone: String::new(),
+            email: String::new(),
+            coach: String::new(),
+            products: String::new(),
+            packages: String::new(),
+            extra_fields,
+            confirm_new_team: false,
+        };
+
+        // No key at all would otherwise land on Ava, the first blank-barcode row
+        let keyless = tauri::async_runtime::block_on(save_player_data_with(&path, update(HashMap::new()), &settings));
+        assert!(matches!(AppError::from(keyless.unwrap_err()), AppError::Validation { .. }));
+
+        let order_id = HashMap::from([("Order ID".to_string(), "ORD-18".to_string())]);
+        let saved = tauri::async_runtime::block_on(save_player_data_with(&path, update(order_id), &settings)).unwrap();
+        assert_eq!(saved.player.first_name, "Benji");
+
+        let restored = tauri::async_runtime::block_on(undo_last_edit_with(&path, &settings)).unwrap();
+        assert_eq!(restored.first_name, "Ben");
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        let names: Vec<&str> = data.players.iter().map(|p| p.first_name.as_str()).collect();
+        assert_eq!(names, vec!["Ava", "Ben"]);
+    }
+
+    #[test]
+    fn file_lock_is_shared_per_path() {
+        let a = file_lock(Path::new("/tmp/lock_roster_a.csv"));
+        assert!(Arc::ptr_eq(&a, &file_lock(Path::new("/tmp/lock_roster_a.csv"))));
+        assert!(!Arc::ptr_eq(&a, &file_lock(Path::new("/tmp/lock_roster_b.csv"))));
+
+        let _held = a.try_lock().unwrap();
+        assert!(file_lock(Path::new("/tmp/lock_roster_a.csv")).try_lock().is_err());
+    }
+
+    #[test]
+    fn delete_player_removes_only_the_matching_barcode() {
+        let path = write_fixture(
+            "delete_player.csv",
+            "Barcode Number,Team,First Name,Last Name,Jersey Number,Coach,Cell Phone,Email,Products,Packages\n\
+             1001,U10 Red,Ava,Smith,7,N,,,,\n\
+             1002,U10 Red,Ben,Jones,12,N,,,,\n",
+        );
+
+        tauri::async_runtime::block_on(delete_player_data(&path, "1001")).unwrap();
+        assert!(tauri::async_runtime::block_on(delete_player_data(&path, "9999")).is_err());
+
+        let data = tauri::async_runtime::block_on(load_csv_file(&path)).unwrap();
+        assert_eq!(data.players.len(), 1);
+        assert_eq!(data.players[0].barcode, "1002");
+    }
 }
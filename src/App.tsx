@@ -11,6 +11,7 @@ import { EmailAutocomplete } from './components/EmailAutocomplete';
 import { ToastContainer, toast } from './components/Toast';
 import { TauriFileOperations } from './tauriFileOperations';
 import { ErrorHandler, AppError, ErrorCodes } from './errorHandling';
+import { backendErrorMessage, RustFileDiff } from './rustBackend';
 import {
   cleanCoachSuffixFromFullName,
   cleanNoOrderSuffixFromFullName,
@@ -144,7 +145,8 @@ function App() {
 
   // Removed browser-based file processing functions - using native desktop dialog instead
 
-  const handleTauriFileSelect = useCallback(async () => {
+  // presetPath skips the dialog, e.g. when reopening from File > Recent
+  const handleTauriFileSelect = useCallback(async (presetPath?: string) => {
     if (isLoadingFile) return;
     
     const errorHandler = ErrorHandler.getInstance();
@@ -155,7 +157,7 @@ function App() {
     
     try {
       // Use standard Tauri dialog plugin
-      const filePath = await fileOps.selectCSVFile();
+      const filePath = presetPath ?? await fileOps.selectCSVFile();
       
       if (!filePath) {
         console.log('No file selected');
@@ -184,6 +186,7 @@ function App() {
       
       setCsvData(frontendData);
       console.log('✅ CSV data loaded successfully, teams:', frontendData.teams);
+      invoke('add_recent_file', { filePath }).catch(error => console.warn('Failed to update recent files:', error));
       
       if (frontendData.teams.length > 0) {
         setSelectedTeam(frontendData.teams[0]);
@@ -336,7 +339,7 @@ function App() {
       const appError = error instanceof AppError
         ? error
         : new AppError(
-            `Tauri auto-save failed: ${error instanceof Error ? error.message : String(error)}`,
+            `Tauri auto-save failed: ${backendErrorMessage(error)}`,
             ErrorCodes.SAVE_ERROR,
             { 
               playerBarcode: selectedPlayer.barcode,
@@ -471,18 +474,109 @@ function App() {
     };
   }, [saveCurrentPlayer, handleTauriFileSelect]);
 
+  // Listen for File > Recent menu event
+  useEffect(() => {
+    const unlisten = listen<string>('menu-open-recent', async (event) => {
+      console.log('🕘 Menu: Open recent triggered', event.payload);
+      await saveCurrentPlayer();
+      handleTauriFileSelect(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [saveCurrentPlayer, handleTauriFileSelect]);
+
+  // Listen for show-in-folder menu event
+  useEffect(() => {
+    const unlisten = listen('menu-reveal-in-folder', async () => {
+      console.log('📁 Menu: Show in folder triggered');
+      if (!originalFilePath) {
+        toast.error('Open a roster first');
+        return;
+      }
+      try {
+        await invoke('reveal_in_folder', { filePath: originalFilePath });
+      } catch (error) {
+        console.error('Show in folder failed:', error);
+        toast.error(backendErrorMessage(error));
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [originalFilePath]);
+
+  // Run update.sh through the backend, showing its latest output line in a toast
+  const runCapturedUpdate = async () => {
+    const toastId = toast.loading('Updating app...');
+    const unlistenOutput = await listen<{ stream: string; line: string }>('update-output', event => {
+      console.log(`[update ${event.payload.stream}]`, event.payload.line);
+      if (event.payload.line.trim()) {
+        toast.update(toastId, event.payload.line, 'loading');
+      }
+    });
+    const unlistenFinished = await listen<{ success: boolean; code: number | null }>('update-finished', event => {
+      unlistenOutput();
+      unlistenFinished();
+      if (event.payload.success) {
+        toast.update(toastId, 'Update installed. Restart the app to use it.', 'success');
+      } else {
+        toast.update(toastId, `Update failed (exit code ${event.payload.code ?? 'unknown'}). See the console for details.`, 'error');
+      }
+    });
+
+    try {
+      await invoke('run_update_captured');
+    } catch (error) {
+      unlistenOutput();
+      unlistenFinished();
+      toast.dismiss(toastId);
+      throw error;
+    }
+  };
+
   // Listen for update app menu event
   useEffect(() => {
     const unlisten = listen('menu-update-app', async () => {
       console.log('🔄 Menu: Update app triggered');
+
+      // Only run the update when there's something to apply
+      try {
+        const info = await invoke<{ available: boolean; behind: number; latest_summary: string | null }>('check_for_update');
+        const version = await invoke<string>('app_version');
+        if (!info.available) {
+          toast.success(`The app is already up to date (version ${version}).`);
+          return;
+        }
+        if (!window.confirm(`You're on version ${version}.\nUpdate available: ${info.latest_summary}\n\nInstall it now?`)) {
+          return;
+        }
+      } catch (error) {
+        console.error('Failed to check for updates:', error);
+        if (!window.confirm(`Couldn't check for updates: ${backendErrorMessage(error)}\n\nRun the update anyway?`)) {
+          return;
+        }
+      }
+
       await saveCurrentPlayer();
+
+      // Prefer running the script in-app so updates work without a terminal emulator
+      try {
+        await runCapturedUpdate();
+        return;
+      } catch (error) {
+        console.warn('In-app update unavailable, falling back to a terminal:', error);
+      }
+
       try {
         await invoke('run_update');
         // Close the app after launching the update script
         await exit(0);
       } catch (error) {
         console.error('Failed to run update:', error);
-        alert(`Failed to start update: ${error}`);
+        alert(`Failed to start update: ${backendErrorMessage(error)}`);
       }
     });
 
@@ -497,11 +591,16 @@ function App() {
       console.log('📥 Menu: Git pull triggered');
       const toastId = toast.loading('Pulling latest changes...');
       try {
+        // A missing SSH key otherwise fails deep inside the clone
+        if (!(await invoke<boolean>('check_git_access'))) {
+          toast.update(toastId, "This computer's SSH key isn't set up for the barcodes repository. Ask for help adding it to GitHub.", 'error');
+          return;
+        }
         const result = await invoke<string>('git_pull');
         toast.update(toastId, result, 'success');
       } catch (error) {
         console.error('Git pull failed:', error);
-        toast.update(toastId, `Git pull failed: ${error}`, 'error');
+        toast.update(toastId, backendErrorMessage(error), 'error');
       }
     });
 
@@ -514,6 +613,25 @@ function App() {
   useEffect(() => {
     const unlisten = listen('menu-git-push', async () => {
       console.log('📤 Menu: Git push triggered');
+
+      // Show what will be committed before asking for a message
+      try {
+        const diffs = await invoke<RustFileDiff[]>('git_diff');
+        if (diffs.length > 0) {
+          const lines = diffs.map(d =>
+            d.untracked ? `  new  ${d.path}` : `  +${d.added ?? '?'} -${d.removed ?? '?'}  ${d.path}`
+          );
+          if (!window.confirm(`These changes will be pushed:\n\n${lines.join('\n')}\n\nContinue?`)) {
+            console.log('Git push cancelled at preview');
+            return;
+          }
+        }
+      } catch (error) {
+        console.error('Git diff preview failed:', error);
+        toast.error(backendErrorMessage(error));
+        return;
+      }
+
       const commitMessage = window.prompt('Enter commit message:');
 
       if (!commitMessage) {
@@ -523,11 +641,68 @@ function App() {
 
       const toastId = toast.loading('Pushing changes...');
       try {
-        const result = await invoke<string>('git_push', { commitMessage });
-        toast.update(toastId, result, 'success');
+        const result = await invoke<{ commit_hash: string; summary: string }>('git_push', { commitMessage });
+        toast.update(toastId, `Pushed ${result.commit_hash}: ${result.summary}`, 'success');
       } catch (error) {
         console.error('Git push failed:', error);
-        toast.update(toastId, `${error}`, 'error');
+        toast.update(toastId, backendErrorMessage(error), 'error');
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Listen for git sync status menu event
+  useEffect(() => {
+    const unlisten = listen('menu-git-sync-status', async () => {
+      console.log('🔎 Menu: Git sync status triggered');
+      const toastId = toast.loading('Checking remote...');
+      try {
+        const state = await invoke<{ behind: number | null; summary: string }>('git_sync_state');
+        toast.update(toastId, state.summary, state.behind ? 'error' : 'success');
+      } catch (error) {
+        console.error('Git sync status failed:', error);
+        toast.update(toastId, backendErrorMessage(error), 'error');
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Listen for git branch switch menu event
+  useEffect(() => {
+    const unlisten = listen('menu-git-checkout', async () => {
+      console.log('🔀 Menu: Git checkout triggered');
+      try {
+        const branches = await invoke<{ current: string; local: string[]; remote: string[] }>('git_list_branches');
+        const available = [
+          ...branches.local,
+          ...branches.remote.map(name => name.replace(/^[^/]+\//, '')).filter(name => !branches.local.includes(name)),
+        ];
+        const branch = window.prompt(
+          `Current branch: ${branches.current}\nAvailable: ${available.join(', ')}\n\nSwitch to branch:`
+        );
+
+        if (!branch || branch === branches.current) {
+          console.log('Git checkout cancelled');
+          return;
+        }
+
+        const toastId = toast.loading(`Switching to ${branch}...`);
+        try {
+          const result = await invoke<string>('git_checkout', { branch });
+          toast.update(toastId, result, 'success');
+        } catch (error) {
+          console.error('Git checkout failed:', error);
+          toast.update(toastId, backendErrorMessage(error), 'error');
+        }
+      } catch (error) {
+        console.error('Listing git branches failed:', error);
+        toast.error(backendErrorMessage(error));
       }
     });
 
@@ -623,7 +798,7 @@ function App() {
           <h2>Select CSV File</h2>
           <div className="desktop-file-selection">
             <button
-              onClick={handleTauriFileSelect}
+              onClick={() => handleTauriFileSelect()}
               className="desktop-file-btn"
               disabled={isLoadingFile}
               title="Open CSV file for editing"
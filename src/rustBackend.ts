@@ -14,6 +14,9 @@ export interface RustPlayer {
   email: string;
   products: string;
   packages: string;
+  // Item codes split out of products/packages by the backend
+  product_items?: string[];
+  package_items?: string[];
   other_fields: Record<string, string>;
 }
 
@@ -21,17 +24,89 @@ export interface RustCSVData {
   players: RustPlayer[];
   teams: string[];
   file_path: string;
+  headers: string[];
+  delimiter: string;
+  quote_style: 'Necessary' | 'Always';
+  line_ending: 'Lf' | 'Crlf';
+  // Present when a .csv-dialect.json beside the roster decided how it was read
+  dialect?: RustCsvDialect | null;
+  skipped_rows: RustRowError[];
+  duplicates: string[];
+}
+
+export interface RustCsvDialect {
+  delimiter: string | null;
+  quote: string | null;
+  has_headers: boolean;
+  columns: string[];
+}
+
+export interface RustRowError {
+  line: number;
+  message: string;
 }
 
 export interface RustPlayerUpdate {
   barcode: string;
+  team: string;
   first_name: string;
   last_name: string;
+  jersey_number: string;
   cell_phone: string;
   email: string;
   coach: string;
   products: string;
   packages: string;
+  // Values for the roster's extra (non-typed) columns, keyed by header
+  extra_fields?: Record<string, string>;
+  // Go ahead with a team name the backend flagged as a likely typo
+  confirm_new_team?: boolean;
+}
+
+export interface RustSaveResult {
+  player: RustPlayer;
+  teams: string[];
+  changed: boolean;
+}
+
+export interface RustWriteResult {
+  path: string;
+  changed: boolean;
+}
+
+// Payload of the `roster-changed` event sent after a save, add or delete
+export interface RustRosterChanged {
+  file_path: string;
+  barcode: string;
+}
+
+// One changed file in the barcodes repo, as previewed before a push
+export interface RustFileDiff {
+  path: string;
+  added: number | null;
+  removed: number | null;
+  staged: boolean;
+  untracked: boolean;
+}
+
+// Error returned by every Tauri command
+export interface RustAppError {
+  kind: 'FileNotFound' | 'Parse' | 'Validation' | 'NotFound' | 'Git' | 'Io' | 'ReadOnly' | 'NeedsConfirmation';
+  message: string;
+  suggestion?: string | null;
+  raw?: string | null;
+}
+
+export function isRustAppError(error: unknown): error is RustAppError {
+  return typeof error === 'object' && error !== null && 'kind' in error && 'message' in error;
+}
+
+// Human-readable text for a rejected invoke, including git's suggestion when present
+export function backendErrorMessage(error: unknown): string {
+  if (isRustAppError(error)) {
+    return [error.message, error.suggestion].filter(Boolean).join(' ');
+  }
+  return error instanceof Error ? error.message : String(error);
 }
 
 // Convert frontend Player to Rust format
@@ -142,15 +217,28 @@ export class RustBackend {
     return await invoke<RustCSVData>('load_csv', { filePath });
   }
 
-  static async savePlayer(filePath: string, playerUpdate: RustPlayerUpdate): Promise<void> {
-    return await invoke<void>('save_player', { filePath, playerUpdate });
+  static async savePlayer(filePath: string, playerUpdate: RustPlayerUpdate): Promise<RustSaveResult> {
+    return await invoke<RustSaveResult>('save_player', { filePath, playerUpdate });
+  }
+
+  // Crash-recovery autosave of an unsaved player form; save_player clears it
+  static async saveDraft(draft: RustPlayerUpdate): Promise<void> {
+    await invoke('save_draft', { draft });
+  }
+
+  static async getDraft(barcode: string): Promise<RustPlayerUpdate | null> {
+    return await invoke<RustPlayerUpdate | null>('get_draft', { barcode });
+  }
+
+  static async clearDraft(barcode: string): Promise<void> {
+    await invoke('clear_draft', { barcode });
   }
 
   static async createBackup(filePath: string): Promise<string> {
     return await invoke<string>('create_backup', { filePath });
   }
 
-  static async writeCsvFile(filePath: string, csvContent: string): Promise<void> {
-    return await invoke<void>('write_csv_file', { filePath, csvContent });
+  static async writeCsvFile(filePath: string, csvContent: string, directory?: string): Promise<RustWriteResult> {
+    return await invoke<RustWriteResult>('write_csv_file', { filePath, csvContent, directory });
   }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+@echo off
+setlocal
+
+set "SCRIPT_DIR=%~dp0"
+if "%SCRIPT_DIR:~-1%"=="\" set "SCRIPT_DIR=%SCRIPT_DIR:~0,-1%"
+for %%I in ("%SCRIPT_DIR%\..") do set "PARENT_DIR=%%~fI"
+set "APP_NAME=MVS Photo Form Filler"
+
+echo ========================================
+echo   MVS Photo Form Filler - Update
+echo ========================================
+echo.
+
+rem Change to the script's directory (should be the repo)
+cd /d "%SCRIPT_DIR%"
+
+rem Validate we're in the app's repo; the folder name is configurable in the app,
+rem so check for the project itself rather than a fixed directory name
+if not exist "src-tauri\tauri.conf.json" (
+    echo ERROR: Script is not in the MVS Photo Form Filler repository.
+    echo Script location: %SCRIPT_DIR%
+    echo Expected to find: src-tauri\tauri.conf.json
+    echo.
+    pause
+    exit /b 1
+)
+
+rem Validate this is a git repository
+if not exist ".git" (
+    echo ERROR: %SCRIPT_DIR% is not a git repository.
+    echo The .git directory was not found.
+    echo.
+    pause
+    exit /b 1
+)
+
+echo Repository: %SCRIPT_DIR%
+echo.
+
+echo Pulling latest changes...
+git pull
+if errorlevel 1 (
+    echo ERROR: git pull failed!
+    pause
+    exit /b 1
+)
+echo.
+
+echo Building app (this may take a few minutes)...
+call bun run tauri:build
+if errorlevel 1 (
+    echo ERROR: Build failed!
+    pause
+    exit /b 1
+)
+echo.
+
+echo Installing...
+copy /y "%SCRIPT_DIR%\src-tauri\target\release\tauri-app.exe" "%PARENT_DIR%\%APP_NAME%.exe" >nul
+if errorlevel 1 (
+    echo ERROR: Install failed!
+    pause
+    exit /b 1
+)
+echo Installed to: %PARENT_DIR%\%APP_NAME%.exe
+echo.
+
+echo ========================================
+echo   Update complete!
+echo   You can now reopen the app.
+echo ========================================
+echo.
+pause
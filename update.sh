@@ -15,7 +15,6 @@ export PATH="$HOME/.bun/bin:$HOME/.cargo/bin:$PATH"
 SCRIPT_DIR="$(cd "$(dirname "$0")" && pwd)"
 PARENT_DIR="$(dirname "$SCRIPT_DIR")"
 APP_NAME="MVS Photo Form Filler"
-REPO_NAME="MVS-form-filler"
 
 echo "========================================"
 echo "  MVS Photo Form Filler - Update"
@@ -25,12 +24,12 @@ echo ""
 # Change to the script's directory (should be the repo)
 cd "$SCRIPT_DIR"
 
-# Validate we're in the correct repo directory
-CURRENT_DIR_NAME="$(basename "$SCRIPT_DIR")"
-if [ "$CURRENT_DIR_NAME" != "$REPO_NAME" ]; then
-    echo "ERROR: Script is not in the expected $REPO_NAME directory."
+# Validate we're in the app's repo; the folder name is configurable in the app,
+# so check for the project itself rather than a fixed directory name
+if [ ! -f "src-tauri/tauri.conf.json" ]; then
+    echo "ERROR: Script is not in the MVS Photo Form Filler repository."
     echo "Script location: $SCRIPT_DIR"
-    echo "Expected directory name: $REPO_NAME"
+    echo "Expected to find: src-tauri/tauri.conf.json"
     echo ""
     read -p "Press Enter to close..."
     exit 1